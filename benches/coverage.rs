@@ -0,0 +1,30 @@
+//! Times working out the anti-aliased pixels of a line, as `DrawSDL` does for every line it draws each frame,
+//! both with a new buffer for each line and with one `Coverage` kept between them. Run it with
+//! `cargo bench --bench coverage`.
+
+#[macro_use]
+extern crate criterion;
+extern crate dataplotlib;
+
+use criterion::Criterion;
+use dataplotlib::draw::{polyline_coverage, Coverage};
+
+/// A wave of `n` points across a 1000 by 600 pixel plot area
+fn wave(n: usize) -> Vec<(f64, f64)> {
+    (0..n).map(|i| (i as f64 * 1000.0 / n as f64, 300.0 + 250.0 * (i as f64 * 0.05).sin())).collect()
+}
+
+fn coverage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("polyline_coverage");
+    for &n in &[100, 1000, 10_000] {
+        let points = wave(n);
+        group.bench_function(format!("{} points", n), |b| b.iter(|| polyline_coverage(&points, 2.0)));
+
+        let mut coverage = Coverage::new();
+        group.bench_function(format!("{} points reused", n), |b| b.iter(|| coverage.polyline(&points, 2.0).len()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, coverage);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+//! Times drawing a frame of 100 000 line segments through `DrawSDL`, with and without batching, from the first
+//! drawing call until the UI thread has rasterised and presented the frame. Needs a display to open the window on.
+
+#[macro_use]
+extern crate criterion;
+extern crate dataplotlib;
+
+use criterion::Criterion;
+use dataplotlib::draw::{Drawable, Quality, Range, Range2d};
+use dataplotlib::draw_sdl::DrawSDL;
+
+const SEGMENTS: usize = 100_000;
+
+/// Draws one frame of a zigzag across the window, one segment at a time, like a line plot does, and waits for it
+/// to be on screen
+fn draw_frame(renderer: &mut DrawSDL) {
+    renderer.set_color([255, 255, 255, 255]);
+    renderer.clear();
+    renderer.set_color([0, 0, 200, 255]);
+    for i in 0..SEGMENTS {
+        let x = i as f64 / SEGMENTS as f64;
+        let next = (i + 1) as f64 / SEGMENTS as f64;
+        let y = |i: usize| if i.is_multiple_of(2) { 0.25 } else { 0.75 };
+        renderer.thick_line((x, y(i)), (next, y(i + 1)), 1);
+    }
+    renderer.present();
+
+    // the UI thread runs its work in order, and handling events waits for it, so the frame is presented by now
+    renderer.get_events();
+}
+
+fn frame_time(c: &mut Criterion) {
+    let mut renderer = DrawSDL::new(dataplotlib::sdl2_init());
+    let unit = Range { min: 0.0, max: 1.0 };
+    renderer.set_view(Range2d(unit, unit));
+
+    // the lines are drawn the same way as before batching, so only the batching differs
+    renderer.set_quality(Quality::Fast);
+
+    let mut group = c.benchmark_group("100k segments");
+    group.sample_size(10);
+
+    group.bench_function("unbatched", |b| {
+        renderer.set_batched(false);
+        b.iter(|| draw_frame(&mut renderer))
+    });
+    group.bench_function("batched", |b| {
+        renderer.set_batched(true);
+        b.iter(|| draw_frame(&mut renderer))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, frame_time);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Times thinning out huge lines and many lines with `lod`, which runs in parallel with the `use-rayon` feature.
+//! Compare `cargo bench --bench lod` with `cargo bench --bench lod --features use-rayon`.
+
+#[macro_use]
+extern crate criterion;
+extern crate dataplotlib;
+
+use criterion::Criterion;
+use dataplotlib::draw::{Range, Range2d};
+use dataplotlib::lod::{self, Line};
+
+/// A random walk of `n` points, with a simple generator so every run thins out the same line
+fn random_walk(n: usize, seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    let mut y = 0.0;
+    (0..n)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            y += (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+            y
+        })
+        .collect()
+}
+
+fn decimate(c: &mut Criterion) {
+    let n = 10_000_000;
+    let xs: Vec<f64> = (0..n).map(|i| i as f64).collect();
+    let ys = random_walk(n, 1);
+    let view = Range2d(Range { min: 0.0, max: n as f64 }, Range { min: -2000.0, max: 2000.0 });
+
+    // a 1000 by 600 pixel plot area
+    let pixel = (view.0.size() / 1000.0, view.1.size() / 600.0);
+
+    let mut group = c.benchmark_group("10M points");
+    group.sample_size(10);
+    group.bench_function("sorted", |b| b.iter(|| lod::decimate(&xs, &ys, true, view, pixel)));
+
+    // the same walk traced as a path through x and y, which has to be thinned out point by point
+    let walk = random_walk(n, 2);
+    let path = Range2d(Range { min: -2000.0, max: 2000.0 }, Range { min: -2000.0, max: 2000.0 });
+    let pixel = (path.0.size() / 1000.0, path.1.size() / 600.0);
+    group.bench_function("unsorted", |b| b.iter(|| lod::decimate(&walk, &ys, false, path, pixel)));
+    group.finish();
+}
+
+fn decimate_all(c: &mut Criterion) {
+    let n = 10_000;
+    let xs: Vec<f64> = (0..n).map(|i| i as f64).collect();
+    let ys: Vec<Vec<f64>> = (0..1000).map(|seed| random_walk(n, seed)).collect();
+    let lines: Vec<Line> = ys.iter().map(|ys| Line { xs: &xs, ys, sorted: true }).collect();
+    let view = Range2d(Range { min: 0.0, max: n as f64 }, Range { min: -200.0, max: 200.0 });
+    let pixel = (view.0.size() / 1000.0, view.1.size() / 600.0);
+
+    let mut group = c.benchmark_group("1000 series of 10k points");
+    group.sample_size(10);
+    group.bench_function("decimate_all", |b| b.iter(|| lod::decimate_all(&lines, view, pixel)));
+    group.finish();
+}
+
+criterion_group!(benches, decimate, decimate_all);
+criterion_main!(benches);
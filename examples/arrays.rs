@@ -0,0 +1,33 @@
+extern crate dataplotlib;
+extern crate ndarray;
+use ndarray::{Array1, Array2};
+use dataplotlib::colormap::Colormap;
+use dataplotlib::density::Density;
+use dataplotlib::figure::Figure;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // Five damped waves, one per column, over a shared x array
+    let x = Array1::linspace(0.0, 10.0, 500);
+    let waves = Array2::from_shape_fn((500, 5), |(i, j)| (x[i] * (j + 1) as f64 * 0.5).sin() * (-x[i] / 5.0).exp());
+
+    let mut lines = PlotBuilder2D::new();
+    lines.add_array_columns(x, waves, Colormap::Viridis);
+
+    // A field of f32 values, drawn as a heatmap with one cell per element
+    let field = Array2::from_shape_fn((60, 80), |(j, i)| (i as f32 / 8.0).sin() * (j as f32 / 6.0).cos());
+    let mut heatmap = PlotBuilder2D::new();
+    heatmap.add_density(Density::heatmap_array(&field));
+
+    let mut figure = Figure::new(1, 2);
+    figure.add(0, 0, lines);
+    figure.add(0, 1, heatmap);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
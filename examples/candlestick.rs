@@ -0,0 +1,43 @@
+extern crate dataplotlib;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use dataplotlib::finance::{self, Candles, Ohlc};
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // Twelve weeks of made up trading, starting on a Monday, with nothing on the weekends
+    let monday = UNIX_EPOCH + Duration::from_secs(19_723 * 86_400);
+    let mut price = 100.0;
+    let mut records = Vec::new();
+    for day in 0..84u64 {
+        if day % 7 >= 5 {
+            continue;
+        }
+        let time: SystemTime = monday + Duration::from_secs(day * 86_400);
+        let drift = (day as f64 * 0.7).sin() * 2.0 + (day as f64 * 0.13).cos();
+        let open = price;
+        let close = price + drift;
+        let high = open.max(close) + 0.5 + (day as f64 * 1.3).sin().abs();
+        let low = open.min(close) - 0.5 - (day as f64 * 0.9).cos().abs();
+        let volume = 1000.0 + 400.0 * (day as f64 * 0.4).sin().abs() + 100.0 * drift.abs();
+        records.push(Ohlc::new(time, open, high, low, close).with_volume(volume));
+        price = close;
+    }
+
+    // Creates a new plot builder, with candlesticks on a date axis
+    let candles = Candles::new(records);
+    let mut pb = PlotBuilder2D::new();
+    pb.add_candles(candles.clone());
+    pb.title = Some("ACME".to_string());
+    pb.y_label = Some("price".to_string());
+
+    // Puts the volume below the prices
+    let figure = finance::with_volume(pb, &candles);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
@@ -0,0 +1,20 @@
+extern crate dataplotlib;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let runs = vec![("baseline", 12.5), ("tuned", 9.1), ("cached", 4.3), ("parallel", 2.8)];
+    let target = vec![("baseline", 10.0), ("tuned", 8.0), ("cached", 5.0), ("parallel", 3.0)];
+
+    // Creates a new plot builder, the category names become the x-axis labels
+    let mut pb = PlotBuilder2D::new();
+    pb.add_category_bars(runs, [0.2, 0.4, 0.8, 1.0]);
+    pb.add_category_x(target, [0.9, 0.1, 0.1, 1.0]);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
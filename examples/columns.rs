@@ -0,0 +1,29 @@
+extern crate dataplotlib;
+use std::sync::Arc;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // Ten million samples from a 16 bit sensor, with their sample numbers
+    let n = 10_000_000u32;
+    let samples: Vec<i16> = (0..n).map(|i| ((i as f32 / 40_000.0).sin() * 20_000.0) as i16 + (i % 97) as i16).collect();
+    let smoothed: Vec<f32> = (0..n).map(|i| (i as f32 / 40_000.0).sin() * 20_000.0).collect();
+
+    // The sample numbers are shared by both lines, and nothing is copied or converted to f64
+    let index: Arc<[u32]> = (0..n).collect::<Vec<u32>>().into();
+
+    // Creates a new plot builder
+    let mut pb = PlotBuilder2D::new();
+    pb.add_columns(index.clone(), samples, [0.6, 0.6, 0.6, 1.0]);
+    pb.add_columns(index, smoothed, [0.8, 0.0, 0.0, 1.0]);
+
+    pb.x_label = Some("sample".to_string());
+    pb.title = Some("columns of i16, f32 and u32 values".to_string());
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
@@ -0,0 +1,45 @@
+extern crate dataplotlib;
+use dataplotlib::util::linspace;
+use dataplotlib::contour::Contour;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let x = linspace(-3, 3, 120);
+    let y = linspace(-3, 3, 120);
+
+    // Two gaussian wells and a hill
+    let z = y.iter()
+        .map(|y| {
+            x.iter()
+                .map(|x| {
+                    let well1 = -(-((x - 1.0).powi(2) + (y - 1.0).powi(2))).exp();
+                    let well2 = -(-((x + 1.0).powi(2) + (y + 1.0).powi(2)) / 0.5).exp();
+                    let hill = 0.5 * (-(x.powi(2) + (y - 2.0).powi(2)) / 2.0).exp();
+                    well1 + well2 + hill
+                })
+                .collect()
+        })
+        .collect();
+
+    // Creates a new plot builder
+    let mut pb = PlotBuilder2D::new();
+
+    // Adds the field as filled bands, with labeled contour lines on top
+    let mut bands = Contour::new(x.clone(), y.clone(), z);
+    bands.filled = true;
+    let mut lines = bands.clone();
+    lines.filled = false;
+    lines.labels = true;
+    lines.color = Some([0.0, 0.0, 0.0, 1.0]);
+
+    pb.add_contour(bands);
+    pb.add_contour(lines);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
@@ -0,0 +1,66 @@
+extern crate dataplotlib;
+use dataplotlib::density::{Density, Reduce};
+use dataplotlib::colormap::Colormap;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::figure::Figure;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+use std::f64::consts::PI;
+
+/// A small xorshift generator, so the example doesn't need any other crates
+struct Random(u64);
+
+impl Random {
+    fn uniform(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A normally distributed number, from the Box-Muller transform
+    fn normal(&mut self) -> f64 {
+        let (u, v) = (self.uniform().max(1e-300), self.uniform());
+        (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+    }
+}
+
+fn main() {
+    // A million points from two overlapping clusters, far too many to see as a scatter plot
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    let points: Vec<(f64, f64)> = (0..1_000_000)
+        .map(|i| {
+            if i % 3 == 0 {
+                (2.0 + 0.5 * random.normal(), 1.0 + 0.8 * random.normal())
+            } else {
+                let (x, y) = (random.normal(), random.normal());
+                (x, 0.6 * x + 0.5 * y)
+            }
+        })
+        .collect();
+
+    // Counts in hexagonal bins. Zooming in bins the points again, more finely.
+    let mut counts = PlotBuilder2D::new();
+    counts.add_density(Density::hexbin(points.clone()));
+    counts.title = Some("hexbin".to_string());
+
+    // The mean distance from the middle of each rectangular bin
+    let mut mean = Density::hist2d(points.clone());
+    mean.values = Some(points.iter().map(|&(x, y)| x.hypot(y)).collect());
+    mean.reduce = Reduce::Mean;
+    mean.colormap = Colormap::Plasma;
+    let mut means = PlotBuilder2D::new();
+    means.add_density(mean);
+    means.title = Some("mean distance".to_string());
+
+    let mut figure = Figure::new(1, 2);
+    figure.add(0, 0, counts);
+    figure.add(0, 1, means);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
@@ -0,0 +1,36 @@
+extern crate dataplotlib;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::distribution::{BoxPlot, Violin};
+use dataplotlib::figure::Figure;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // Some made up measurements from three runs
+    let runs: Vec<(&str, Vec<f64>)> = vec![
+        ("run 1", (0..200).map(|i| 5.0 + ((i * 7919) % 200) as f64 / 100.0).collect()),
+        ("run 2", (0..200).map(|i| 6.0 + ((i * 104_729) % 300) as f64 / 60.0).collect()),
+        ("run 3", (0..200).map(|i| if i % 2 == 0 { 3.0 } else { 8.0 } + ((i * 7) % 20) as f64 / 20.0).collect()),
+    ];
+
+    let mut boxes = BoxPlot::new(runs.clone());
+    boxes.notch = true;
+
+    let mut box_pb = PlotBuilder2D::new();
+    box_pb.add_box_plot(boxes);
+    box_pb.title = Some("box plot".to_string());
+
+    let mut violin_pb = PlotBuilder2D::new();
+    violin_pb.add_violin(Violin::new(runs));
+    violin_pb.title = Some("violin plot".to_string());
+
+    let mut figure = Figure::new(1, 2);
+    figure.add(0, 0, box_pb);
+    figure.add(0, 1, violin_pb);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
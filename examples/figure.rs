@@ -0,0 +1,44 @@
+extern crate dataplotlib;
+use dataplotlib::util::{linspace, zip2};
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::figure::Figure;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let x = linspace(0, 10, 200);
+
+    let y_sin = x.iter().map(|x| x.sin()).collect();
+    let y_cos = x.iter().map(|x| x.cos()).collect();
+    let y_sq = x.iter().map(|x| x * x).collect();
+
+    let mut sin = PlotBuilder2D::new();
+    sin.add_color_xy(zip2(&x, &y_sin), [1.0, 0.0, 0.0, 1.0]);
+    sin.title = Some("sin".to_string());
+
+    let mut cos = PlotBuilder2D::new();
+    cos.add_color_xy(zip2(&x, &y_cos), [0.0, 0.0, 1.0, 1.0]);
+    cos.title = Some("cos".to_string());
+
+    let mut square = PlotBuilder2D::new();
+    square.add_color_xy(zip2(&x, &y_sq), [0.0, 0.6, 0.0, 1.0]);
+    square.x_label = Some("x".to_string());
+    square.y_label = Some("x^2".to_string());
+
+    // Two plots side by side on top, and one spanning the whole bottom row
+    let mut figure = Figure::new(2, 2);
+    figure.title = Some("Subplots".to_string());
+    let sin = figure.add(0, 0, sin);
+    let cos = figure.add(0, 1, cos);
+    figure.add_span(1, 0, 1, 2, square);
+
+    // Zooming or panning either of the top plots moves both of them
+    figure.share_x(cos, sin);
+    figure.share_y(cos, sin);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
@@ -0,0 +1,34 @@
+extern crate dataplotlib;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // A random walk of ten million steps, from a small xorshift generator
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut walk = 0.0;
+    let xy = (0..10_000_000)
+        .map(|i| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            walk += (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5;
+            (i as f64 / 1e6, walk)
+        })
+        .collect();
+
+    // Creates a new plot builder
+    let mut pb = PlotBuilder2D::new();
+
+    // Only as much of the walk as the screen can show is drawn, so zooming in stays quick
+    pb.add_color_xy(xy, [0.0, 0.3, 0.8, 1.0]);
+
+    pb.x_label = Some("million steps".to_string());
+    pb.title = Some("scroll to zoom into the detail".to_string());
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
@@ -0,0 +1,29 @@
+extern crate dataplotlib;
+use dataplotlib::util::{linspace, zip2};
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::{Link, LinkAxes, Plotter};
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let x = linspace(0, 100, 1000);
+
+    let y_signal = x.iter().map(|x| (x / 3.0).sin() + (x / 17.0).cos()).collect();
+    let y_error = x.iter().map(|x| (x / 5.0).sin() * 0.1).collect();
+
+    let mut signal = PlotBuilder2D::new();
+    signal.add_color_xy(zip2(&x, &y_signal), [0.0, 0.0, 1.0, 1.0]);
+    signal.title = Some("signal".to_string());
+
+    let mut error = PlotBuilder2D::new();
+    error.add_color_xy(zip2(&x, &y_error), [1.0, 0.0, 0.0, 1.0]);
+    error.title = Some("error".to_string());
+
+    // Zooming, dragging or resetting (r) either window moves the other along the x axis
+    let link = Link::new(LinkAxes::X);
+
+    let sdlh = dataplotlib::sdl2_init();
+
+    let mut plt = Plotter::new();
+    plt.plot2d_linked(signal, DrawSDL::new(sdlh.clone()), &link);
+    plt.plot2d_linked(error, DrawSDL::new(sdlh), &link);
+}
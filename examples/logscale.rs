@@ -0,0 +1,28 @@
+extern crate dataplotlib;
+use dataplotlib::util::{linspace, zip2};
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::scale::Scale;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // frequencies from 1 Hz to 1 MHz, evenly spaced in decades
+    let f: Vec<f64> = linspace(0, 6, 300).iter().map(|e| 10f64.powf(*e)).collect();
+
+    // magnitude response of a first order low pass filter with a 1 kHz cutoff
+    let gain = f.iter().map(|f| 1.0 / (1.0 + (f / 1000.0).powi(2)).sqrt()).collect();
+    let response = zip2(&f, &gain);
+
+    // Creates a new plot builder with log scales on both axes
+    let mut pb = PlotBuilder2D::new();
+    pb.x_scale = Scale::Log10;
+    pb.y_scale = Scale::Log10;
+
+    pb.add_color_xy(response, [0.0, 0.0, 0.8, 1.0]);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
@@ -0,0 +1,38 @@
+extern crate dataplotlib;
+use dataplotlib::pie::Pie;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::figure::Figure;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let budget = vec![("staff", 52.0), ("hardware", 18.0), ("licenses", 12.0), ("travel", 6.0), ("other", 12.0)];
+
+    // A pie chart with the biggest slice pulled out
+    let mut pie = Pie::new(budget.clone());
+    pie.explode = vec![0.1];
+
+    let mut left = PlotBuilder2D::new();
+    left.add_pie(pie);
+    left.title = Some("budget".to_string());
+
+    // The same numbers as a donut chart, starting from the right and going counterclockwise
+    let mut donut = Pie::new(budget);
+    donut.inner_radius = 0.5;
+    donut.start_angle = 0.0;
+    donut.clockwise = false;
+
+    let mut right = PlotBuilder2D::new();
+    right.add_pie(donut);
+    right.title = Some("as a donut".to_string());
+
+    let mut figure = Figure::new(1, 2);
+    figure.add(0, 0, left);
+    figure.add(0, 1, right);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
@@ -0,0 +1,44 @@
+extern crate dataplotlib;
+use dataplotlib::util::zip2;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::polar::Polar;
+use dataplotlib::figure::Figure;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+use std::f64::consts::PI;
+
+fn main() {
+    // The gain of a three petal antenna pattern, all the way around
+    let theta: Vec<f64> = (0..361).map(|i| i as f64 * PI / 180.0).collect();
+    let gain = theta.iter().map(|t| (3.0 * t).cos().abs()).collect();
+
+    let mut antenna = PlotBuilder2D::new();
+    antenna.polar = Some(Polar::new());
+    antenna.add_color_xy(zip2(&theta, &gain), [0.8, 0.0, 0.0, 1.0]);
+    antenna.title = Some("antenna pattern".to_string());
+
+    // How often the wind blew from each of 16 directions, drawn like a compass
+    let directions: Vec<f64> = (0..16).map(|i| i as f64 * PI / 8.0).collect();
+    let counts = directions.iter().map(|d| 5.0 + 4.0 * d.sin() + 2.0 * (2.0 * d).cos()).collect();
+
+    let mut compass = Polar::new();
+    compass.zero = 90.0;
+    compass.clockwise = true;
+    compass.angle_step = 30.0;
+
+    let mut wind = PlotBuilder2D::new();
+    wind.polar = Some(compass);
+    wind.add_color_bars(zip2(&directions, &counts), [0.2, 0.5, 0.8, 1.0]);
+    wind.title = Some("wind rose".to_string());
+
+    let mut figure = Figure::new(1, 2);
+    figure.add(0, 0, antenna);
+    figure.add(0, 1, wind);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
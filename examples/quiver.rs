@@ -0,0 +1,36 @@
+extern crate dataplotlib;
+use dataplotlib::util::linspace;
+use dataplotlib::colormap::Colormap;
+use dataplotlib::quiver::Quiver;
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let x = linspace(-2, 2, 21);
+    let y = linspace(-2, 2, 21);
+
+    // A vortex, spinning counterclockwise and slowing down away from the middle
+    let field = |x: f64, y: f64| {
+        let falloff = (-(x * x + y * y) / 2.0).exp();
+        (-y * falloff, x * falloff)
+    };
+    let u: Vec<Vec<f64>> = y.iter().map(|&y| x.iter().map(|&x| field(x, y).0).collect()).collect();
+    let v: Vec<Vec<f64>> = y.iter().map(|&y| x.iter().map(|&x| field(x, y).1).collect()).collect();
+
+    // Colors the arrows by their speed, with a reference arrow for scale
+    let mut quiver = Quiver::grid(&x, &y, &u, &v);
+    quiver.colormap = Some(Colormap::Viridis);
+    quiver.key = Some(0.5);
+
+    // Creates a new plot builder
+    let mut pb = PlotBuilder2D::new();
+    pb.add_quiver(quiver);
+    pb.title = Some("vortex".to_string());
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
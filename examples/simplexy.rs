@@ -1,5 +1,5 @@
 extern crate dataplotlib;
-use dataplotlib::util::{linspace, zip2};
+use dataplotlib::util::linspace;
 use dataplotlib::plotbuilder::PlotBuilder2D;
 use dataplotlib::plotter::Plotter;
 use dataplotlib::draw_sdl::DrawSDL;
@@ -7,17 +7,15 @@ use dataplotlib::draw_sdl::DrawSDL;
 fn main() {
     let x = linspace(0, 10, 100);
 
-    let y_sin = x.iter().map(|x| x.sin()).collect();
-    let xy_sin = zip2(&x, &y_sin);
-
-    let xy_lin = zip2(&x, &x);
-
     // Creates a new plot builder
     let mut pb = PlotBuilder2D::new();
 
-    // Adds the sin plot and the cos plot
-    pb.add_simple_xy(xy_sin);
-    pb.add_simple_xy(xy_lin);
+    // Adds the sin plot from a closure, and the linear plot from the x values twice
+    pb.add_fn(x.clone(), |x| x.sin(), [1.0, 0.0, 0.0, 1.0]);
+    pb.add_xs_ys(x.clone(), x, [1.0, 0.0, 0.0, 1.0]);
+
+    // Adds a few y values from an array, at x = 0, 1, 2 and so on
+    pb.add_y([1u8, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1], [0.0, 0.0, 1.0, 1.0]);
 
     let sdlh = dataplotlib::sdl2_init();
     let sdl2_window = DrawSDL::new(sdlh);
@@ -0,0 +1,34 @@
+extern crate dataplotlib;
+use dataplotlib::util::zip2;
+use dataplotlib::plotbuilder::{PlotBuilder2D, Step};
+use dataplotlib::figure::Figure;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    // A decaying sine wave, sampled 32 times
+    let n: Vec<f64> = (0..32).map(|i| i as f64).collect();
+    let samples: Vec<f64> = n.iter().map(|n| (n * 0.4).sin() * (-n / 20.0).exp()).collect();
+
+    // The signal held between samples, like a zero order hold
+    let mut held = PlotBuilder2D::new();
+    held.add_step_xy(zip2(&n, &samples), Step::Post, [0.0, 0.4, 0.8, 1.0]);
+    held.title = Some("step".to_string());
+
+    // The same samples as stems from zero
+    let mut stems = PlotBuilder2D::new();
+    stems.add_stem_xy(zip2(&n, &samples), 0.0, [0.8, 0.2, 0.0, 1.0]);
+    stems.title = Some("stem".to_string());
+    stems.x_label = Some("sample".to_string());
+
+    let mut figure = Figure::new(2, 1);
+    let top = figure.add(0, 0, held);
+    let bottom = figure.add(1, 0, stems);
+    figure.share_x(bottom, top);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot_figure(figure, sdl2_window);
+}
@@ -0,0 +1,48 @@
+extern crate dataplotlib;
+use dataplotlib::util::linspace;
+use dataplotlib::colormap::Colormap;
+use dataplotlib::plotbuilder::PlotBuilder3D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let x = linspace(-3, 3, 40);
+    let y = linspace(-3, 3, 40);
+
+    // A ripple spreading out from the middle
+    let z: Vec<Vec<f64>> = y.iter()
+        .map(|y| {
+            x.iter()
+                .map(|x| {
+                    let r = (x * x + y * y).sqrt();
+                    (2.0 * r).cos() * (-r / 2.0).exp()
+                })
+                .collect()
+        })
+        .collect();
+
+    // A helix above the ripple
+    let helix = (0..200)
+        .map(|i| {
+            let t = i as f64 / 20.0;
+            (2.5 * t.cos(), 2.5 * t.sin(), 1.2 + t / 10.0)
+        })
+        .collect();
+
+    // Creates a new plot builder
+    let mut pb = PlotBuilder3D::new();
+
+    pb.add_surface(x, y, z, Colormap::Viridis);
+    pb.add_color_line(helix, [0.8, 0.0, 0.0, 1.0]);
+
+    pb.x_label = Some("x".to_string());
+    pb.y_label = Some("y".to_string());
+    pb.z_label = Some("height".to_string());
+    pb.title = Some("drag to orbit, scroll to move in and out".to_string());
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot3d(pb, sdl2_window);
+}
@@ -0,0 +1,28 @@
+extern crate dataplotlib;
+use std::time::{Duration, SystemTime};
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let now = SystemTime::now();
+
+    // one sample every 5 minutes over the last three days
+    let samples: Vec<(SystemTime, f64)> = (0..864)
+        .map(|i| {
+            let t = now - Duration::from_secs(300 * (864 - i));
+            let hours = i as f64 / 12.0;
+            (t, 20.0 + 5.0 * (hours * std::f64::consts::PI / 12.0).sin())
+        })
+        .collect();
+
+    // Creates a new plot builder, the x-axis is labeled with dates and times
+    let mut pb = PlotBuilder2D::new();
+    pb.add_time_xy(samples, [0.8, 0.2, 0.0, 1.0]);
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
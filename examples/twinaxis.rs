@@ -0,0 +1,27 @@
+extern crate dataplotlib;
+use std::f64;
+use dataplotlib::util::{linspace, zip2};
+use dataplotlib::plotbuilder::PlotBuilder2D;
+use dataplotlib::plotter::Plotter;
+use dataplotlib::draw_sdl::DrawSDL;
+
+fn main() {
+    let hours = linspace(0, 48, 200);
+
+    let temperature = hours.iter().map(|h| 15.0 + 8.0 * (h / 24.0 * 2.0 * f64::consts::PI).sin()).collect();
+    let pressure = hours.iter().map(|h| 1013.0 + 9.0 * (h / 30.0).cos()).collect();
+
+    // Temperature goes against the left axis, pressure against the right one
+    let mut pb = PlotBuilder2D::new();
+    pb.add_color_xy(zip2(&hours, &temperature), [0.9, 0.1, 0.1, 1.0]);
+    pb.add_color_xy_y2(zip2(&hours, &pressure), [0.1, 0.1, 0.9, 1.0]);
+    pb.x_label = Some("hours".to_string());
+    pb.y_label = Some("temperature (C)".to_string());
+    pb.y2_label = Some("pressure (hPa)".to_string());
+
+    let sdlh = dataplotlib::sdl2_init();
+    let sdl2_window = DrawSDL::new(sdlh);
+
+    let mut plt = Plotter::new();
+    plt.plot2d(pb, sdl2_window);
+}
@@ -0,0 +1,160 @@
+//! **camera** projects 3 dimensional points onto the screen for `PlotBuilder3D` plots.
+//!
+//! The data of a 3D plot is fitted into the cube from -1 to 1 along each axis, and the camera orbits the center of that cube.
+
+use std::f64;
+
+/// Points closer to the camera than this are not drawn
+const NEAR: f64 = 0.05;
+
+/// `Camera` looks at the center of a 3D plot from a point on a sphere around it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera {
+    /// **azimuth** is the angle of the camera around the z-axis, in degrees counterclockwise from the x-axis
+    pub azimuth: f64,
+
+    /// **elevation** is the angle of the camera above the xy-plane, in degrees. It is kept between -89 and 89.
+    pub elevation: f64,
+
+    /// **distance** is how far the camera is from the center of the plot, where the plot's data fills a cube 2 units wide
+    pub distance: f64,
+
+    /// **fov** is the vertical field of view, in degrees
+    pub fov: f64,
+}
+
+/// The directions the camera looks along, and where it is
+struct Basis {
+    eye: [f64; 3],
+    forward: [f64; 3],
+    right: [f64; 3],
+    up: [f64; 3],
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let length = dot(a, a).sqrt();
+    [a[0] / length, a[1] / length, a[2] / length]
+}
+
+impl Camera {
+    /// `new` creates a camera looking down on the plot from the front left, a bit like a drafting table
+    pub fn new() -> Camera {
+        Camera {
+            azimuth: -60.0,
+            elevation: 30.0,
+            distance: 6.0,
+            fov: 30.0,
+        }
+    }
+
+    /// `orbit` turns the camera around the center of the plot by the given angles in degrees
+    pub fn orbit(&mut self, azimuth: f64, elevation: f64) {
+        self.azimuth = (self.azimuth + azimuth).rem_euclid(360.0);
+        self.elevation = (self.elevation + elevation).clamp(-89.0, 89.0);
+    }
+
+    /// `dolly` moves the camera towards the center of the plot for positive `steps`, and away for negative ones
+    pub fn dolly(&mut self, steps: f64) {
+        self.distance = (self.distance * 0.9f64.powf(steps)).clamp(2.0, 50.0);
+    }
+
+    fn basis(&self) -> Basis {
+        let (az, el) = (self.azimuth.to_radians(), self.elevation.to_radians());
+        let eye = [self.distance * el.cos() * az.cos(), self.distance * el.cos() * az.sin(), self.distance * el.sin()];
+        let forward = normalize([-eye[0], -eye[1], -eye[2]]);
+        let right = normalize(cross(forward, [0.0, 0.0, 1.0]));
+        let up = cross(right, forward);
+        Basis { eye, forward, right, up }
+    }
+
+    /// `direction` returns the unit vector from the center of the plot towards the camera
+    pub fn direction(&self) -> [f64; 3] {
+        let forward = self.basis().forward;
+        [-forward[0], -forward[1], -forward[2]]
+    }
+
+    /// `project` returns where each point shows up on the screen, as `(x, y, depth)`.
+    /// `x` and `y` are 1 at the top and right edges of the field of view, and `depth` is the distance in front of the camera.
+    /// Points behind the camera are `None`.
+    pub fn project(&self, points: &[[f64; 3]]) -> Vec<Option<(f64, f64, f64)>> {
+        let basis = self.basis();
+        let focal = 1.0 / (self.fov.to_radians() / 2.0).tan();
+        points
+            .iter()
+            .map(|p| {
+                let v = [p[0] - basis.eye[0], p[1] - basis.eye[1], p[2] - basis.eye[2]];
+                let depth = dot(v, basis.forward);
+                if depth < NEAR {
+                    return None;
+                }
+                Some((dot(v, basis.right) * focal / depth, dot(v, basis.up) * focal / depth, depth))
+            })
+            .collect()
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Camera {
+        Camera::new()
+    }
+}
+
+/// `surface_normal` returns the unit normal of the quad `a, b, c, d`, going around its corners in order
+pub fn surface_normal(a: [f64; 3], b: [f64; 3], c: [f64; 3], d: [f64; 3]) -> [f64; 3] {
+    let diagonal_1 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let diagonal_2 = [d[0] - b[0], d[1] - b[1], d[2] - b[2]];
+    let normal = cross(diagonal_1, diagonal_2);
+    if dot(normal, normal) == 0.0 {
+        [0.0, 0.0, 1.0]
+    } else {
+        normalize(normal)
+    }
+}
+
+/// `brightness` shades a surface with the given normal, lit from just above and to the left of the camera
+pub fn brightness(normal: [f64; 3], camera: &Camera) -> f64 {
+    let basis = camera.basis();
+    let light = normalize([
+        -basis.forward[0] + 0.5 * basis.up[0] - 0.5 * basis.right[0],
+        -basis.forward[1] + 0.5 * basis.up[1] - 0.5 * basis.right[1],
+        -basis.forward[2] + 0.5 * basis.up[2] - 0.5 * basis.right[2],
+    ]);
+
+    // both sides of a surface are lit the same, since either can face the camera
+    0.35 + 0.65 * dot(normal, light).abs()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn project_test() {
+        let mut camera = Camera::new();
+        camera.azimuth = 0.0;
+        camera.elevation = 0.0;
+        camera.fov = 90.0;
+
+        let projected = camera.project(&[[0.0, 0.0, 0.0], [0.0, 1.0, 1.0], [10.0, 0.0, 0.0]]);
+
+        // the center is straight ahead, and the camera looks along -x with +y to the right
+        let (x, y, depth) = projected[0].unwrap();
+        assert!(x.abs() < 1e-12 && y.abs() < 1e-12 && (depth - 6.0).abs() < 1e-12);
+        let (x, y, _) = projected[1].unwrap();
+        assert!((x - 1.0 / 6.0).abs() < 1e-12 && (y - 1.0 / 6.0).abs() < 1e-12);
+        assert!(projected[2].is_none());
+
+        camera.orbit(0.0, 200.0);
+        assert_eq!(camera.elevation, 89.0);
+        camera.dolly(100.0);
+        assert_eq!(camera.distance, 2.0);
+    }
+}
@@ -0,0 +1,79 @@
+//! **colormap** maps scalar values onto colors, for plots that encode a value as a color.
+
+/// `Colormap` selects one of the built-in color gradients
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Colormap {
+    /// Perceptually uniform dark blue -> green -> yellow
+    Viridis,
+
+    /// Perceptually uniform dark blue -> magenta -> yellow
+    Plasma,
+
+    /// Diverging blue -> light gray -> red, useful for values centered on zero
+    Coolwarm,
+
+    /// Black -> white
+    Grayscale,
+}
+
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [71, 44, 122],
+    [59, 81, 139],
+    [44, 113, 142],
+    [33, 144, 141],
+    [39, 173, 129],
+    [92, 200, 99],
+    [170, 220, 50],
+    [253, 231, 37],
+];
+
+const PLASMA: [[u8; 3]; 9] = [
+    [13, 8, 135],
+    [75, 3, 161],
+    [125, 3, 168],
+    [168, 34, 150],
+    [203, 70, 121],
+    [229, 107, 93],
+    [248, 148, 65],
+    [253, 195, 40],
+    [240, 249, 33],
+];
+
+const COOLWARM: [[u8; 3]; 5] = [[59, 76, 192], [124, 159, 249], [221, 221, 221], [244, 154, 123], [180, 4, 38]];
+
+const GRAYSCALE: [[u8; 3]; 2] = [[0, 0, 0], [255, 255, 255]];
+
+impl Colormap {
+    /// `color` returns the color at position `t` along the gradient, where `t` is clamped to `[0, 1]`
+    pub fn color(&self, t: f64) -> [f32; 4] {
+        let stops: &[[u8; 3]] = match *self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Plasma => &PLASMA,
+            Colormap::Coolwarm => &COOLWARM,
+            Colormap::Grayscale => &GRAYSCALE,
+        };
+
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let pos = t * (stops.len() - 1) as f64;
+        let i = (pos.floor() as usize).min(stops.len() - 2);
+        let frac = (pos - i as f64) as f32;
+
+        let mut color = [1.0; 4];
+        for c in 0..3 {
+            let a = stops[i][c] as f32 / 255.0;
+            let b = stops[i + 1][c] as f32 / 255.0;
+            color[c] = a + (b - a) * frac;
+        }
+        color
+    }
+
+    /// `color_in` returns the color for `value` when the gradient spans `min..max`
+    pub fn color_in(&self, value: f64, min: f64, max: f64) -> [f32; 4] {
+        if max > min {
+            self.color((value - min) / (max - min))
+        } else {
+            self.color(0.5)
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! **column** holds the x or y values of a line in a column of their own, so big datasets can be plotted without copying them.
+//!
+//! A `Column` keeps its values in whatever number type they came in, such as `f32` or `i16`, and reads each one as an
+//! `f64` only when it is needed. Its buffer is shared through an `Arc`, so the same data can go into several plots,
+//! and stay with the caller too, while it is only held once in memory.
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[cfg(feature = "use-ndarray")]
+use ndarray::{ArcArray1, ArcArray2, Array1, ArrayBase, ArrayView1, Data, Ix1};
+
+/// `Value` is a number type that can be plotted
+pub trait Value: Copy + Send + Sync + 'static {
+    /// The value as an `f64`, which may round very large integers
+    fn to_f64(self) -> f64;
+
+    /// `values` as a slice of `f64`s, if they already are `f64`s
+    fn as_f64(values: &[Self]) -> Option<&[f64]> {
+        let _ = values;
+        None
+    }
+}
+
+impl Value for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn as_f64(values: &[f64]) -> Option<&[f64]> {
+        Some(values)
+    }
+}
+
+macro_rules! impl_value {
+    ($($t:ty),*) => {
+        $(impl Value for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_value!(f32, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// `Values` is a column of numbers, read one at a time as `f64`s
+pub trait Values: Send + Sync {
+    /// The number of values
+    fn len(&self) -> usize;
+
+    /// The value at `i`, as an `f64`
+    fn at(&self, i: usize) -> f64;
+
+    /// The values as a slice, if they are already `f64`s, so they can be read without converting them
+    fn as_f64(&self) -> Option<&[f64]>;
+
+    /// Whether there are no values at all
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Value> Values for [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        T::as_f64(self)
+    }
+}
+
+impl<T: Value> Values for Vec<T> {
+    fn len(&self) -> usize {
+        self[..].len()
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        T::as_f64(self)
+    }
+}
+
+impl<T: Value> Values for Arc<[T]> {
+    fn len(&self) -> usize {
+        self[..].len()
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        T::as_f64(self)
+    }
+}
+
+impl<T: Value> Values for Arc<Vec<T>> {
+    fn len(&self) -> usize {
+        self[..].len()
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        T::as_f64(self)
+    }
+}
+
+/// One dimensional arrays are read in place, whether they own their values or not
+#[cfg(feature = "use-ndarray")]
+impl<S, T> Values for ArrayBase<S, Ix1>
+where
+    S: Data<Elem = T> + Send + Sync,
+    T: Value,
+{
+    fn len(&self) -> usize {
+        ArrayBase::len(self)
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        self.as_slice().and_then(T::as_f64)
+    }
+}
+
+/// One column of a shared two dimensional array, read in place
+#[cfg(feature = "use-ndarray")]
+struct ArrayColumn<T> {
+    array: ArcArray2<T>,
+    column: usize,
+}
+
+#[cfg(feature = "use-ndarray")]
+impl<T: Value> Values for ArrayColumn<T> {
+    fn len(&self) -> usize {
+        self.array.nrows()
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self.array[[i, self.column]].to_f64()
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        self.array.column(self.column).to_slice().and_then(T::as_f64)
+    }
+}
+
+/// `Column` is a shared column of plot values.
+/// Cloning a `Column` only shares its buffer, and never copies the values.
+#[derive(Clone)]
+pub struct Column(Arc<dyn Values>);
+
+impl Deref for Column {
+    type Target = dyn Values;
+
+    fn deref(&self) -> &(dyn Values + 'static) {
+        &*self.0
+    }
+}
+
+impl Values for Column {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn at(&self, i: usize) -> f64 {
+        self.0.at(i)
+    }
+
+    fn as_f64(&self) -> Option<&[f64]> {
+        self.0.as_f64()
+    }
+}
+
+#[cfg(feature = "use-ndarray")]
+impl Column {
+    /// `array_column` is the column `column` of `array`, sharing its buffer without copying it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `array` has no such column.
+    pub fn array_column<T: Value>(array: ArcArray2<T>, column: usize) -> Column {
+        assert!(column < array.ncols(), "no column {} in an array of {} columns", column, array.ncols());
+        Column(Arc::new(ArrayColumn { array, column }))
+    }
+}
+
+/// Takes the `Vec` over as it is, without copying it
+impl<T: Value> From<Vec<T>> for Column {
+    fn from(values: Vec<T>) -> Column {
+        Column(Arc::new(values))
+    }
+}
+
+/// Shares the buffer with the caller
+impl<T: Value> From<Arc<[T]>> for Column {
+    fn from(values: Arc<[T]>) -> Column {
+        Column(Arc::new(values))
+    }
+}
+
+/// Shares the buffer with the caller
+impl<T: Value> From<Arc<Vec<T>>> for Column {
+    fn from(values: Arc<Vec<T>>) -> Column {
+        Column(Arc::new(values))
+    }
+}
+
+/// Takes the array over as it is, without copying it
+#[cfg(feature = "use-ndarray")]
+impl<T: Value> From<Array1<T>> for Column {
+    fn from(values: Array1<T>) -> Column {
+        Column(Arc::new(values))
+    }
+}
+
+/// Shares the buffer with the caller
+#[cfg(feature = "use-ndarray")]
+impl<T: Value> From<ArcArray1<T>> for Column {
+    fn from(values: ArcArray1<T>) -> Column {
+        Column(Arc::new(values))
+    }
+}
+
+/// Copies the values once, in their own number type, like borrowed slices
+#[cfg(feature = "use-ndarray")]
+impl<'a, T: Value> From<ArrayView1<'a, T>> for Column {
+    fn from(values: ArrayView1<'a, T>) -> Column {
+        Column(Arc::new(values.to_vec()))
+    }
+}
+
+/// Plots are drawn on a thread of their own, so borrowed values are copied once, in their own number type.
+/// Pass an `Arc` instead to share them without copying.
+impl<'a, T: Value> From<&'a [T]> for Column {
+    fn from(values: &'a [T]) -> Column {
+        Column(Arc::new(values.to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn column_test() {
+        // values keep their own type, and are read as f64s
+        let small: Column = vec![1.5f32, -2.0, 3.25].into();
+        assert_eq!(small.len(), 3);
+        assert_eq!(small.at(1), -2.0);
+        assert!(small.as_f64().is_none());
+
+        // shared buffers are not copied, even when the column is cloned
+        let shared: Arc<[f64]> = vec![0.0, 1.0, 2.0].into();
+        let column = Column::from(shared.clone());
+        let copy = column.clone();
+        assert_eq!(copy.as_f64().unwrap().as_ptr(), shared.as_ptr());
+        assert_eq!(Arc::strong_count(&shared), 2);
+
+        let counts: Column = (&[7u8, 255][..]).into();
+        assert_eq!(counts.at(1), 255.0);
+    }
+
+    #[cfg(feature = "use-ndarray")]
+    #[test]
+    fn array_test() {
+        use ndarray::{arr1, Array2, ShapeBuilder};
+
+        // owned arrays of f64s are read from their own buffer
+        let xs = arr1(&[0.0, 0.5, 1.0]);
+        let ptr = xs.as_ptr();
+        let column = Column::from(xs);
+        assert_eq!(column.as_f64().unwrap().as_ptr(), ptr);
+
+        // a column of a 2D array isn't contiguous, so it is read one value at a time
+        let grid = Array2::from_shape_vec((2, 2), vec![1i32, 2, 3, 4]).unwrap();
+        let view = grid.column(1);
+        assert!(view.as_f64().is_none());
+        assert_eq!(view.at(1), 4.0);
+        assert_eq!(Column::from(view).at(0), 2.0);
+
+        // columns of a shared array read from its buffer too
+        let shared = grid.into_shared();
+        let column = Column::array_column(shared.clone(), 1);
+        assert_eq!((column.len(), column.at(0), column.at(1)), (2, 2.0, 4.0));
+
+        // and contiguous columns of f64s are read as a slice of that buffer
+        let fortran = Array2::from_shape_vec((2, 2).f(), vec![1.0, 2.0, 3.0, 4.0]).unwrap().into_shared();
+        let column = Column::array_column(fortran.clone(), 1);
+        assert_eq!(column.as_f64().unwrap().as_ptr(), &fortran[[0, 1]] as *const f64);
+    }
+}
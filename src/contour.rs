@@ -0,0 +1,381 @@
+//! **contour** extracts contour lines and filled contour bands from a scalar field sampled on a grid.
+//!
+//! Contour lines are found with marching squares, and the pieces are joined into continuous polylines.
+//! Filled bands are found by splitting every grid cell into two triangles and clipping them to the band,
+//! which is exact for the piecewise linear interpolation that the contour lines use.
+
+use std::collections::HashMap;
+use std::f64;
+
+use colormap::Colormap;
+use scale::{format_number, nice_step};
+
+/// `Levels` decides which values of the field get a contour
+#[derive(Clone, Debug)]
+pub enum Levels {
+    /// Roughly this many evenly spaced levels at "nice" round values
+    Auto(usize),
+
+    /// Exactly these levels
+    Values(Vec<f64>),
+}
+
+/// `Contour` describes a contour plot of `zs`, where `zs[j][i]` is the value at `(xs[i], ys[j])`
+#[derive(Clone, Debug)]
+pub struct Contour {
+    /// **xs** contains the x coordinates of the grid columns, in increasing order
+    pub xs: Vec<f64>,
+
+    /// **ys** contains the y coordinates of the grid rows, in increasing order
+    pub ys: Vec<f64>,
+
+    /// **zs** contains one row of values per entry in `ys`, each with one value per entry in `xs`.
+    /// `NaN` values leave a hole in the plot.
+    pub zs: Vec<Vec<f64>>,
+
+    /// **levels** decides where the contours are drawn
+    pub levels: Levels,
+
+    /// **colormap** colors the lines or bands by their level
+    pub colormap: Colormap,
+
+    /// **color** optionally draws every contour line in one color instead of using the colormap
+    pub color: Option<[f32; 4]>,
+
+    /// **filled** draws filled bands between the levels instead of lines
+    pub filled: bool,
+
+    /// **labels** writes the level next to each contour line. Ignored for filled contours.
+    pub labels: bool,
+}
+
+impl Contour {
+    /// `new` creates an unlabeled line contour plot with 10 automatic levels, colored along `Colormap::Viridis`
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>, zs: Vec<Vec<f64>>) -> Contour {
+        Contour {
+            xs,
+            ys,
+            zs,
+            levels: Levels::Auto(10),
+            colormap: Colormap::Viridis,
+            color: None,
+            filled: false,
+            labels: false,
+        }
+    }
+
+    /// `z_range` returns the smallest and largest finite values in the field
+    pub fn z_range(&self) -> (f64, f64) {
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for &z in self.zs.iter().flat_map(|row| row.iter()) {
+            if z.is_finite() {
+                min = min.min(z);
+                max = max.max(z);
+            }
+        }
+        (min, max)
+    }
+
+    /// `level_values` resolves `levels` into the list of values to contour, in increasing order.
+    /// Levels that are not finite are left out.
+    pub fn level_values(&self) -> Vec<f64> {
+        match self.levels {
+            Levels::Values(ref values) => {
+                let mut values: Vec<f64> = values.iter().cloned().filter(|l| l.is_finite()).collect();
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                values
+            }
+            Levels::Auto(count) => {
+                let (min, max) = self.z_range();
+                auto_levels(min, max, count)
+            }
+        }
+    }
+
+    /// `band_bounds` returns the boundaries between filled bands: the levels, extended to cover the whole field
+    pub fn band_bounds(&self) -> Vec<f64> {
+        let (min, max) = self.z_range();
+        let mut bounds = vec![min];
+        bounds.extend(self.level_values().into_iter().filter(|&l| l > min && l < max));
+        bounds.push(max);
+        bounds
+    }
+}
+
+/// `auto_levels` picks about `count` levels at round values strictly between `min` and `max`
+pub fn auto_levels(min: f64, max: f64, count: usize) -> Vec<f64> {
+    if max.is_nan() || min.is_nan() || max <= min || count == 0 {
+        return vec![];
+    }
+
+    let step = nice_step((max - min) / count as f64);
+    let mut levels = Vec::new();
+    let mut i = (min / step).floor() as i64;
+    loop {
+        let level = i as f64 * step;
+        if level >= max {
+            break;
+        }
+        if level > min {
+            levels.push(level);
+        }
+        i += 1;
+    }
+    levels
+}
+
+/// `format_level` writes a level with as many decimals as its neighbours need to be told apart
+pub fn format_level(level: f64, levels: &[f64]) -> String {
+    let step = levels
+        .windows(2)
+        .map(|w| (w[1] - w[0]).abs())
+        .filter(|&d| d > 0.0)
+        .fold(f64::INFINITY, f64::min);
+    format_number(level, if step.is_finite() { step } else { level })
+}
+
+/// Identifies a grid edge, so that contour pieces from neighbouring cells can be joined exactly
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Edge {
+    /// The edge from (i, j) to (i + 1, j)
+    Horizontal(usize, usize),
+
+    /// The edge from (i, j) to (i, j + 1)
+    Vertical(usize, usize),
+}
+
+/// A piece of a contour line crossing one cell, from a point on one edge to a point on another
+type Segment = (Edge, (f64, f64), Edge, (f64, f64));
+
+fn interpolate(a: (f64, f64, f64), b: (f64, f64, f64), level: f64) -> (f64, f64) {
+    let t = if b.2 != a.2 { (level - a.2) / (b.2 - a.2) } else { 0.5 };
+    (a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1))
+}
+
+/// The corners of the grid cell from `(xs[i], ys[j])` to `(xs[i + 1], ys[j + 1])`, going around it.
+/// Values missing from a short or ragged `zs` come out as `NaN`, which leaves a hole like any other `NaN`.
+fn cell_corners(xs: &[f64], ys: &[f64], zs: &[Vec<f64>], i: usize, j: usize) -> [(f64, f64, f64); 4] {
+    let z = |i: usize, j: usize| zs.get(j).and_then(|row| row.get(i)).cloned().unwrap_or(f64::NAN);
+    [
+        (xs[i], ys[j], z(i, j)),
+        (xs[i + 1], ys[j], z(i + 1, j)),
+        (xs[i + 1], ys[j + 1], z(i + 1, j + 1)),
+        (xs[i], ys[j + 1], z(i, j + 1)),
+    ]
+}
+
+/// `contour_lines` finds where the field crosses `level`, as a list of polylines
+pub fn contour_lines(xs: &[f64], ys: &[f64], zs: &[Vec<f64>], level: f64) -> Vec<Vec<(f64, f64)>> {
+    let mut segments: Vec<Segment> = Vec::new();
+
+    for j in 0..ys.len().saturating_sub(1) {
+        for i in 0..xs.len().saturating_sub(1) {
+            let corners = cell_corners(xs, ys, zs, i, j);
+            if corners.iter().any(|c| !c.2.is_finite()) {
+                continue;
+            }
+
+            let mut case = 0;
+            for (bit, corner) in corners.iter().enumerate() {
+                if corner.2 >= level {
+                    case |= 1 << bit;
+                }
+            }
+
+            // edges are numbered bottom, right, top, left
+            let edges = [Edge::Horizontal(i, j), Edge::Vertical(i + 1, j), Edge::Horizontal(i, j + 1), Edge::Vertical(i, j)];
+            let points = [
+                interpolate(corners[0], corners[1], level),
+                interpolate(corners[1], corners[2], level),
+                interpolate(corners[3], corners[2], level),
+                interpolate(corners[0], corners[3], level),
+            ];
+
+            let center_above = corners.iter().map(|c| c.2).sum::<f64>() / 4.0 >= level;
+            let pairs: &[(usize, usize)] = match case {
+                1 | 14 => &[(3, 0)],
+                2 | 13 => &[(0, 1)],
+                3 | 12 => &[(3, 1)],
+                4 | 11 => &[(1, 2)],
+                6 | 9 => &[(0, 2)],
+                7 | 8 => &[(3, 2)],
+                5 if center_above => &[(0, 1), (3, 2)],
+                5 => &[(3, 0), (1, 2)],
+                10 if center_above => &[(3, 0), (1, 2)],
+                10 => &[(0, 1), (3, 2)],
+                _ => &[],
+            };
+
+            for &(a, b) in pairs {
+                segments.push((edges[a], points[a], edges[b], points[b]));
+            }
+        }
+    }
+
+    join_segments(segments)
+}
+
+/// Joins segments that share an edge into polylines
+fn join_segments(segments: Vec<Segment>) -> Vec<Vec<(f64, f64)>> {
+    let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        by_edge.entry(segment.0).or_default().push(index);
+        by_edge.entry(segment.2).or_default().push(index);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+
+        let (edge_a, point_a, edge_b, point_b) = segments[start];
+        let mut line = vec![point_a, point_b];
+
+        // extend forward from the end of the first segment, then backward from its start
+        for &(first_edge, forward) in &[(edge_b, true), (edge_a, false)] {
+            let mut edge = first_edge;
+            while let Some(&next) = by_edge[&edge].iter().find(|&&s| !used[s]) {
+                used[next] = true;
+                let (ea, pa, eb, pb) = segments[next];
+                let (point, next_edge) = if ea == edge { (pb, eb) } else { (pa, ea) };
+                if forward {
+                    line.push(point);
+                } else {
+                    line.insert(0, point);
+                }
+                edge = next_edge;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Clips the polygon `poly` to the part where the interpolated value is on the `keep` side of `level`
+fn clip_by_value<F>(poly: &[(f64, f64, f64)], level: f64, keep: F) -> Vec<(f64, f64, f64)>
+    where F: Fn(f64) -> bool
+{
+    let mut out = Vec::new();
+    for i in 0..poly.len() {
+        let a = poly[i];
+        let b = poly[(i + 1) % poly.len()];
+        if keep(a.2) {
+            out.push(a);
+        }
+        if keep(a.2) != keep(b.2) {
+            let (x, y) = interpolate(a, b, level);
+            out.push((x, y, level));
+        }
+    }
+    out
+}
+
+/// `contour_bands` finds the regions where `lower <= z <= upper`, as a list of polygons
+pub fn contour_bands(xs: &[f64], ys: &[f64], zs: &[Vec<f64>], lower: f64, upper: f64) -> Vec<Vec<(f64, f64)>> {
+    let mut polygons = Vec::new();
+
+    for j in 0..ys.len().saturating_sub(1) {
+        for i in 0..xs.len().saturating_sub(1) {
+            let corners = cell_corners(xs, ys, zs, i, j);
+            if corners.iter().any(|c| !c.2.is_finite()) {
+                continue;
+            }
+
+            // cells entirely inside the band stay whole, which keeps the polygon count down
+            if corners.iter().all(|c| c.2 >= lower && c.2 <= upper) {
+                polygons.push(corners.iter().map(|c| (c.0, c.1)).collect());
+                continue;
+            }
+            if corners.iter().all(|c| c.2 < lower) || corners.iter().all(|c| c.2 > upper) {
+                continue;
+            }
+
+            for triangle in &[[corners[0], corners[1], corners[2]], [corners[0], corners[2], corners[3]]] {
+                let clipped = clip_by_value(triangle, lower, |z| z >= lower);
+                let clipped = clip_by_value(&clipped, upper, |z| z <= upper);
+                if clipped.len() >= 3 {
+                    polygons.push(clipped.iter().map(|c| (c.0, c.1)).collect());
+                }
+            }
+        }
+    }
+
+    polygons
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cone() -> (Vec<f64>, Vec<f64>, Vec<Vec<f64>>) {
+        let xs: Vec<f64> = (0..21).map(|i| i as f64 / 10.0 - 1.0).collect();
+        let ys = xs.clone();
+        let zs = ys.iter().map(|y| xs.iter().map(|x| (x * x + y * y).sqrt()).collect()).collect();
+        (xs, ys, zs)
+    }
+
+    #[test]
+    fn auto_levels_test() {
+        let levels = auto_levels(0.0, 1.0, 5);
+        assert_eq!(levels.len(), 4);
+        assert!((levels[0] - 0.2).abs() < 1e-12 && (levels[3] - 0.8).abs() < 1e-12);
+        assert_eq!(auto_levels(-3.0, 7.0, 10), vec![-2.0, -1.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert!(auto_levels(1.0, 1.0, 5).is_empty());
+    }
+
+    #[test]
+    fn contour_lines_test() {
+        let (xs, ys, zs) = cone();
+        let lines = contour_lines(&xs, &ys, &zs, 0.55);
+
+        // a single closed circle of radius 0.55
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.first(), line.last());
+        for &(x, y) in line {
+            assert!(((x * x + y * y).sqrt() - 0.55).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn contour_bands_test() {
+        let (xs, ys, zs) = cone();
+        let polygons = contour_bands(&xs, &ys, &zs, 0.0, 0.5);
+
+        // the bands are made of planar pieces, so the covered area approximates the disc
+        let area: f64 = polygons
+            .iter()
+            .map(|p| {
+                let mut a = 0.0;
+                for i in 0..p.len() {
+                    let (x0, y0) = p[i];
+                    let (x1, y1) = p[(i + 1) % p.len()];
+                    a += x0 * y1 - x1 * y0;
+                }
+                a.abs() / 2.0
+            })
+            .sum();
+        assert!((area - f64::consts::PI * 0.25).abs() < 0.02);
+    }
+
+    #[test]
+    fn ragged_test() {
+        // rows that are short or missing leave holes instead of panicking
+        let (xs, ys, mut zs) = cone();
+        zs[10].truncate(5);
+        zs.truncate(15);
+        assert!(!contour_lines(&xs, &ys, &zs, 0.55).is_empty());
+        assert!(!contour_bands(&xs, &ys, &zs, 0.0, 0.5).is_empty());
+
+        let mut contour = Contour::new(xs, ys, zs);
+        contour.levels = Levels::Values(vec![0.5, f64::NAN, 0.25]);
+        assert_eq!(contour.level_values(), vec![0.25, 0.5]);
+    }
+}
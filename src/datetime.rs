@@ -0,0 +1,303 @@
+//! **datetime** lets series use points in time as their values, and labels date axes with readable dates.
+//!
+//! Times are stored as seconds since the Unix epoch, and always labeled in UTC.
+//! The tick locator picks seconds, minutes, hours, days, months or years depending on how much time is in view.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use scale::{format_number, nice_step, Tick};
+
+/// `Timestamp` is implemented by the types that can be used as points in time on a date axis
+pub trait Timestamp {
+    /// The number of seconds since the Unix epoch, negative for earlier times
+    fn epoch_seconds(&self) -> f64;
+}
+
+impl Timestamp for SystemTime {
+    fn epoch_seconds(&self) -> f64 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => after.as_secs() as f64 + after.subsec_nanos() as f64 * 1e-9,
+            Err(before) => {
+                let before = before.duration();
+                -(before.as_secs() as f64 + before.subsec_nanos() as f64 * 1e-9)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "use-chrono")]
+impl<Tz: ::chrono::TimeZone> Timestamp for ::chrono::DateTime<Tz> {
+    fn epoch_seconds(&self) -> f64 {
+        self.timestamp() as f64 + self.timestamp_subsec_nanos() as f64 * 1e-9
+    }
+}
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+
+/// Times further than this from the epoch, about 10 000 years, are beyond the dates that ticks are placed on
+const LIMIT: f64 = 1e4 * 365.25 * DAY;
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Days since the epoch for a date in the proleptic Gregorian calendar (months and days count from 1)
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The (year, month, day) of a number of days since the epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// The day that `t` falls on, counting from the epoch. Times beyond `LIMIT` fall on the day at the limit.
+fn day_of(t: f64) -> i64 {
+    (t.clamp(-LIMIT, LIMIT) / DAY).floor() as i64
+}
+
+/// The calendar unit that ticks are spaced in
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+/// The candidate tick spacings, from finest to coarsest. Years beyond these use round multiples.
+const STEPS: [(Unit, i64); 24] = [
+    (Unit::Second, 1),
+    (Unit::Second, 2),
+    (Unit::Second, 5),
+    (Unit::Second, 10),
+    (Unit::Second, 15),
+    (Unit::Second, 30),
+    (Unit::Minute, 1),
+    (Unit::Minute, 2),
+    (Unit::Minute, 5),
+    (Unit::Minute, 10),
+    (Unit::Minute, 15),
+    (Unit::Minute, 30),
+    (Unit::Hour, 1),
+    (Unit::Hour, 2),
+    (Unit::Hour, 3),
+    (Unit::Hour, 6),
+    (Unit::Hour, 12),
+    (Unit::Day, 1),
+    (Unit::Day, 2),
+    (Unit::Day, 5),
+    (Unit::Day, 10),
+    (Unit::Month, 1),
+    (Unit::Month, 3),
+    (Unit::Month, 6),
+];
+
+fn approximate_seconds(unit: Unit) -> f64 {
+    match unit {
+        Unit::Second => 1.0,
+        Unit::Minute => MINUTE,
+        Unit::Hour => HOUR,
+        Unit::Day => DAY,
+        Unit::Month => 30.44 * DAY,
+        Unit::Year => 365.25 * DAY,
+    }
+}
+
+/// Writes a time of day, e.g. `14:05` or `14:05:30`
+fn format_clock(t: f64, seconds: bool) -> String {
+    let of_day = t.rem_euclid(DAY) as i64;
+    let (h, m, s) = (of_day / 3600, of_day / 60 % 60, of_day % 60);
+    if seconds {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", h, m)
+    }
+}
+
+/// Writes a date as month and day, e.g. `Mar 7`
+fn format_day(t: f64) -> String {
+    let (_, month, day) = civil_from_days(day_of(t));
+    format!("{} {}", MONTHS[(month - 1) as usize], day)
+}
+
+/// `format_time` writes a point in time in full, to the second, e.g. `Mar 7 2024 14:05:30`.
+/// Times too far from the epoch to have a date are written as seconds.
+pub fn format_time(t: f64) -> String {
+    if !(-LIMIT..=LIMIT).contains(&t) {
+        return format!("{} s", format_number(t, t.abs()));
+    }
+    let (year, _, _) = civil_from_days(day_of(t));
+    format!("{} {} {}", format_day(t), year, format_clock(t, true))
+}
+
+fn format_tick(t: f64, unit: Unit) -> String {
+    let (year, month, _) = civil_from_days(day_of(t));
+    match unit {
+        Unit::Second => format_clock(t, true),
+        // midnight gets the date, so that multi-day views stay readable
+        Unit::Minute | Unit::Hour if t.rem_euclid(DAY) == 0.0 => format_day(t),
+        Unit::Minute | Unit::Hour => format_clock(t, false),
+        Unit::Day => format_day(t),
+        Unit::Month => format!("{} {}", MONTHS[(month - 1) as usize], year),
+        Unit::Year => format!("{}", year),
+    }
+}
+
+/// `date_ticks` generates roughly `count` ticks at round points in time covering `min..max` seconds since the epoch
+pub fn date_ticks(min: f64, max: f64, count: usize) -> Vec<Tick> {
+    if !min.is_finite() || !max.is_finite() {
+        return vec![];
+    }
+    let (min, max) = (min.max(-LIMIT), max.min(LIMIT));
+    if max <= min {
+        return vec![];
+    }
+
+    let count = count.max(2);
+    let span = max - min;
+    let per_tick = span / count as f64;
+
+    // under a second per tick, the clock time with fractional seconds is all that changes
+    if per_tick < 1.0 {
+        let step = nice_step(per_tick);
+        let (first, last) = ((min / step).ceil(), (max / step).floor());
+
+        // far from the epoch an f64 can't tell such small steps apart
+        if first.abs() > 1e15 || last.abs() > 1e15 {
+            return vec![];
+        }
+        return (first as i64..last as i64 + 1)
+            .map(|i| {
+                let t = i as f64 * step;
+                let fraction = format_number(t.rem_euclid(1.0), step);
+                let fraction = fraction.trim_start_matches('0');
+                Tick {
+                    value: t,
+                    label: format!("{}{}", format_clock(t, true), if fraction.is_empty() { ".0" } else { fraction }),
+                    major: true,
+                }
+            })
+            .collect();
+    }
+
+    let (unit, step) = STEPS
+        .iter()
+        .cloned()
+        .find(|&(unit, step)| approximate_seconds(unit) * step as f64 * count as f64 >= span)
+        .unwrap_or_else(|| {
+            let years = per_tick / approximate_seconds(Unit::Year);
+            (Unit::Year, nice_step(years.max(1.0)) as i64)
+        });
+
+    let mut values = Vec::new();
+    match unit {
+        Unit::Second | Unit::Minute | Unit::Hour => {
+            let step = approximate_seconds(unit) * step as f64;
+            let first = (min / step).ceil() as i64;
+            let last = (max / step).floor() as i64;
+            values.extend((first..last + 1).map(|i| i as f64 * step));
+        }
+        Unit::Day => {
+            // day ticks restart at the first of every month, like a calendar
+            let first = (min / DAY).ceil() as i64;
+            let last = (max / DAY).floor() as i64;
+            for days in first..last + 1 {
+                let (_, _, day) = civil_from_days(days);
+                if (day - 1) % step == 0 && day + step / 2 <= 31 {
+                    values.push(days as f64 * DAY);
+                }
+            }
+        }
+        Unit::Month | Unit::Year => {
+            let (mut year, mut month, _) = civil_from_days(day_of(min));
+            if unit == Unit::Year {
+                month = 1;
+                year -= year.rem_euclid(step);
+            } else {
+                month -= (month - 1) % step;
+            }
+            loop {
+                let t = days_from_civil(year, month, 1) as f64 * DAY;
+                if t > max {
+                    break;
+                }
+                if t >= min {
+                    values.push(t);
+                }
+                if unit == Unit::Year {
+                    year += step;
+                } else {
+                    month += step;
+                    if month > 12 {
+                        month -= 12;
+                        year += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    values
+        .into_iter()
+        .map(|t| Tick {
+            value: t,
+            label: format_tick(t, unit),
+            major: true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn labels(ticks: Vec<Tick>) -> Vec<String> {
+        ticks.into_iter().map(|t| t.label).collect()
+    }
+
+    #[test]
+    fn civil_test() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in -1000..1000 {
+            let (y, m, d) = civil_from_days(days * 37);
+            assert_eq!(days_from_civil(y, m, d), days * 37);
+        }
+    }
+
+    #[test]
+    fn date_ticks_test() {
+        let jan1 = days_from_civil(2024, 1, 1) as f64 * DAY;
+
+        assert_eq!(labels(date_ticks(jan1 + 10.0, jan1 + 50.0, 4)), vec!["00:00:10", "00:00:20", "00:00:30", "00:00:40", "00:00:50"]);
+        assert_eq!(labels(date_ticks(jan1 - HOUR, jan1 + 3.0 * HOUR, 4)), vec!["23:00", "Jan 1", "01:00", "02:00", "03:00"]);
+        assert_eq!(labels(date_ticks(jan1, jan1 + 40.0 * DAY, 5)), vec!["Jan 1", "Jan 11", "Jan 21", "Feb 1"]);
+        assert_eq!(labels(date_ticks(jan1, jan1 + 365.0 * DAY, 4)), vec!["Jan 2024", "Apr 2024", "Jul 2024", "Oct 2024"]);
+        assert_eq!(labels(date_ticks(jan1, jan1 + 30.0 * 365.0 * DAY, 4)), vec!["2030", "2040", "2050"]);
+
+        // zoomed far out, the ticks stop at the dates that can be written
+        let far = date_ticks(-1e300, 1e300, 4);
+        assert!(!far.is_empty() && far.iter().all(|t| t.value.abs() <= LIMIT));
+        assert!(date_ticks(1e20, 1e20 + 0.5, 4).is_empty());
+        assert_eq!(format_time(1e20), "1e20 s");
+    }
+}
@@ -0,0 +1,449 @@
+//! **density** aggregates scatter data into bins, for plots of more points than can be told apart.
+//!
+//! Each bin is colored by how many points fall into it, or by a reduction of a third value over those points.
+//! The bins are laid out over the part of the plot in view, so zooming in gives finer bins.
+//! They line up with multiples of the bin size, so panning doesn't shuffle points between bins.
+//!
+//! Heatmaps and images are density plots whose bins are given already, as the cells of a `Grid`.
+
+use std::collections::HashMap;
+use std::f64;
+
+use colormap::Colormap;
+use draw::{Range, Range2d};
+
+#[cfg(feature = "use-ndarray")]
+use column::Value;
+#[cfg(feature = "use-ndarray")]
+use ndarray::{ArrayBase, Data, Ix2};
+
+/// `BinShape` decides the shape of the bins
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BinShape {
+    /// Rectangles in a grid, for a 2D histogram
+    Rectangle,
+
+    /// Hexagons, which are closer to circles than rectangles and so show the shape of the data a little better
+    Hexagon,
+}
+
+/// `Reduce` decides what value colors a bin
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Reduce {
+    /// The number of points in the bin
+    Count,
+
+    /// The sum of the values of the points in the bin
+    Sum,
+
+    /// The mean of the values of the points in the bin
+    Mean,
+
+    /// The smallest value of the points in the bin
+    Min,
+
+    /// The largest value of the points in the bin
+    Max,
+}
+
+/// `Grid` is a grid of values that are known already, for a heatmap or an image
+#[derive(Clone, Debug)]
+pub struct Grid {
+    /// **values** contains one row of cells per step up the grid, each with one value per step across it.
+    /// `NaN` values leave their cell empty.
+    pub values: Vec<Vec<f64>>,
+
+    /// **x** is the range the grid covers from left to right
+    pub x: Range,
+
+    /// **y** is the range the grid covers from bottom to top
+    pub y: Range,
+}
+
+impl Grid {
+    /// `new` creates a grid of `values` with cells 1 wide and 1 high, from the origin up and to the right
+    pub fn new(values: Vec<Vec<f64>>) -> Grid {
+        let cols = values.iter().map(Vec::len).max().unwrap_or(0);
+        let rows = values.len();
+        Grid {
+            values,
+            x: Range { min: 0.0, max: cols as f64 },
+            y: Range { min: 0.0, max: rows as f64 },
+        }
+    }
+
+    /// `bin` returns a bin for each cell in `view` with a finite value
+    pub fn bin(&self, view: Range2d) -> Bins {
+        let Range2d(w, h) = view;
+        let cols = self.values.iter().map(Vec::len).max().unwrap_or(0);
+        let rows = self.values.len();
+        let size = (self.x.size() / cols as f64, self.y.size() / rows as f64);
+        let mut bins = Bins {
+            shape: BinShape::Rectangle,
+            size,
+            bins: vec![],
+            range: (f64::NAN, f64::NAN),
+        };
+        if cols == 0 || rows == 0 || !(size.0 > 0.0 && size.1 > 0.0) {
+            return bins;
+        }
+
+        // only the cells in view are looked at, so zooming into a big grid stays quick
+        let cells = |view: Range, grid: Range, size: f64, n: usize| {
+            let first = ((view.min - grid.min) / size).floor().max(0.0) as usize;
+            let last = ((view.max - grid.min) / size).ceil().max(0.0) as usize;
+            first.min(n)..last.min(n)
+        };
+        for j in cells(h, self.y, size.1, rows) {
+            let row = &self.values[j];
+            for i in cells(w, self.x, size.0, row.len()) {
+                if row[i].is_finite() {
+                    bins.bins.push(Bin {
+                        center: (self.x.min + (i as f64 + 0.5) * size.0, self.y.min + (j as f64 + 0.5) * size.1),
+                        count: 1,
+                        value: row[i],
+                    });
+                }
+            }
+        }
+        bins.find_range();
+        bins
+    }
+}
+
+#[cfg(feature = "use-ndarray")]
+impl Grid {
+    /// `from_array` creates a grid of `values` like `new`, where `values[[j, i]]` is the cell in row `j` and column `i`
+    pub fn from_array<S: Data<Elem = T>, T: Value>(values: &ArrayBase<S, Ix2>) -> Grid {
+        Grid::new(values.rows().into_iter().map(|row| row.iter().map(|v| v.to_f64()).collect()).collect())
+    }
+}
+
+/// `Density` describes a 2D histogram or hexbin plot of scattered points
+#[derive(Clone, Debug)]
+pub struct Density {
+    /// **points** contains the scattered points. Points that are not finite are left out.
+    pub points: Vec<(f64, f64)>,
+
+    /// **values** optionally gives each point a value to reduce over each bin with `reduce`.
+    /// If `None`, bins are colored by their count.
+    pub values: Option<Vec<f64>>,
+
+    /// **reduce** decides what value colors each bin. Anything but `Reduce::Count` needs `values`.
+    pub reduce: Reduce,
+
+    /// **shape** decides the shape of the bins
+    pub shape: BinShape,
+
+    /// **bins** is the number of bins across the view, in both directions
+    pub bins: usize,
+
+    /// **min_count** leaves bins with fewer points than this empty
+    pub min_count: usize,
+
+    /// **colormap** colors the bins by their value
+    pub colormap: Colormap,
+
+    /// **colorbar** shows the colormap and the values it stands for to the right of the plot
+    pub colorbar: bool,
+
+    /// **grid** gives the bins straight from the cells of a grid, instead of binning `points`
+    pub grid: Option<Grid>,
+}
+
+impl Density {
+    /// `new` creates a density plot of the points in 40 bins across, of the given shape, colored by their count with a colorbar
+    pub fn new(points: Vec<(f64, f64)>, shape: BinShape) -> Density {
+        Density {
+            points,
+            values: None,
+            reduce: Reduce::Count,
+            shape,
+            bins: 40,
+            min_count: 1,
+            colormap: Colormap::Viridis,
+            colorbar: true,
+            grid: None,
+        }
+    }
+
+    /// `heatmap` creates a heatmap of `values`, where `values[j][i]` colors the cell from `(i, j)` to `(i + 1, j + 1)`.
+    /// Change the range it covers through `grid`.
+    pub fn heatmap(values: Vec<Vec<f64>>) -> Density {
+        let mut density = Density::new(vec![], BinShape::Rectangle);
+        density.grid = Some(Grid::new(values));
+        density
+    }
+
+    /// `hist2d` creates a 2D histogram of the points, with rectangular bins
+    pub fn hist2d(points: Vec<(f64, f64)>) -> Density {
+        Density::new(points, BinShape::Rectangle)
+    }
+
+    /// `hexbin` creates a density plot of the points, with hexagonal bins
+    pub fn hexbin(points: Vec<(f64, f64)>) -> Density {
+        Density::new(points, BinShape::Hexagon)
+    }
+
+    /// `bin` sorts the points in `view` into bins, and reduces each bin to its value, or takes the cells of `grid` in `view`
+    pub fn bin(&self, view: Range2d) -> Bins {
+        if let Some(ref grid) = self.grid {
+            return grid.bin(view);
+        }
+
+        let Range2d(w, h) = view;
+        let bins = self.bins.max(1) as f64;
+        let size = (w.size() / bins, h.size() / bins);
+        let mut bins = Bins {
+            shape: self.shape,
+            size,
+            bins: vec![],
+            range: (f64::NAN, f64::NAN),
+        };
+        if !(size.0 > 0.0 && size.1 > 0.0) {
+            return bins;
+        }
+
+        // count on one value per point, even if there are too few values
+        let value = |i: usize| self.values.as_ref().and_then(|v| v.get(i)).cloned().unwrap_or(f64::NAN);
+        let reduce = if self.values.is_some() { self.reduce } else { Reduce::Count };
+
+        let mut found: HashMap<(i64, i64), Accumulator> = HashMap::new();
+        for (i, &(x, y)) in self.points.iter().enumerate() {
+            // points just outside the view still count towards the bins along its edges
+            if !(x >= w.min - size.0 && x <= w.max + size.0 && y >= h.min - size.1 && y <= h.max + size.1) {
+                continue;
+            }
+            let key = match self.shape {
+                BinShape::Rectangle => ((x / size.0).floor() as i64, (y / size.1).floor() as i64),
+                BinShape::Hexagon => hexagon_key(x / size.0, y / size.1),
+            };
+            found.entry(key).or_insert_with(Accumulator::new).add(value(i));
+        }
+
+        let min_count = self.min_count.max(1);
+        for (key, acc) in found {
+            if acc.count < min_count {
+                continue;
+            }
+            let value = acc.reduce(reduce);
+            if !value.is_finite() {
+                continue;
+            }
+            let center = match self.shape {
+                BinShape::Rectangle => ((key.0 as f64 + 0.5) * size.0, (key.1 as f64 + 0.5) * size.1),
+                BinShape::Hexagon => {
+                    let (u, v) = hexagon_center(key);
+                    (u * size.0, v * size.1)
+                }
+            };
+            bins.bins.push(Bin {
+                center,
+                count: acc.count,
+                value,
+            });
+        }
+
+        bins.find_range();
+        bins
+    }
+}
+
+#[cfg(feature = "use-ndarray")]
+impl Density {
+    /// `heatmap_array` creates a heatmap of a 2D array, with row 0 along the bottom
+    pub fn heatmap_array<S: Data<Elem = T>, T: Value>(values: &ArrayBase<S, Ix2>) -> Density {
+        let mut density = Density::new(vec![], BinShape::Rectangle);
+        density.grid = Some(Grid::from_array(values));
+        density
+    }
+
+    /// `image_array` is the same as `heatmap_array`, but with row 0 along the top, the way images are stored
+    pub fn image_array<S: Data<Elem = T>, T: Value>(values: &ArrayBase<S, Ix2>) -> Density {
+        let mut density = Density::heatmap_array(values);
+        if let Some(ref mut grid) = density.grid {
+            grid.values.reverse();
+        }
+        density
+    }
+}
+
+/// `Bin` is one bin with at least `min_count` points
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bin {
+    /// The middle of the bin
+    pub center: (f64, f64),
+
+    /// The number of points in the bin
+    pub count: usize,
+
+    /// The reduced value of the bin
+    pub value: f64,
+}
+
+/// `Bins` is the result of binning a `Density` for one view
+#[derive(Clone, Debug)]
+pub struct Bins {
+    pub shape: BinShape,
+
+    /// The width and height of a rectangular bin. Hexagons are as wide as a rectangle,
+    /// and their rows are `sqrt(3) / 2` of its height apart.
+    pub size: (f64, f64),
+
+    pub bins: Vec<Bin>,
+
+    /// The smallest and largest values of the bins, or `NaN` if there are no bins
+    pub range: (f64, f64),
+}
+
+impl Bins {
+    /// Sets `range` to the smallest and largest values of the bins
+    fn find_range(&mut self) {
+        let min = self.bins.iter().map(|b| b.value).fold(f64::INFINITY, f64::min);
+        let max = self.bins.iter().map(|b| b.value).fold(f64::NEG_INFINITY, f64::max);
+        if min <= max {
+            self.range = (min, max);
+        }
+    }
+
+    /// `outline` returns the corners of a bin
+    pub fn outline(&self, bin: &Bin) -> Vec<(f64, f64)> {
+        let (x, y) = bin.center;
+        let (hw, hh) = (self.size.0 / 2.0, self.size.1 / 2.0);
+        match self.shape {
+            BinShape::Rectangle => vec![(x - hw, y - hh), (x + hw, y - hh), (x + hw, y + hh), (x - hw, y + hh)],
+            BinShape::Hexagon => {
+                // pointy topped, so the rows fit together
+                let radius = 1.0 / 3f64.sqrt();
+                (0..6)
+                    .map(|i| {
+                        let angle = (30.0 + 60.0 * i as f64).to_radians();
+                        (x + radius * angle.cos() * self.size.0, y + radius * angle.sin() * self.size.1)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The count and running totals of the points in one bin
+struct Accumulator {
+    count: usize,
+
+    /// The number of points with a finite value
+    valued: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Accumulator {
+    fn new() -> Accumulator {
+        Accumulator {
+            count: 0,
+            valued: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        if value.is_finite() {
+            self.valued += 1;
+            self.sum += value;
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+    }
+
+    fn reduce(&self, reduce: Reduce) -> f64 {
+        match reduce {
+            Reduce::Count => self.count as f64,
+            Reduce::Sum => self.sum,
+            Reduce::Mean => self.sum / self.valued as f64,
+            Reduce::Min => self.min,
+            Reduce::Max => self.max,
+        }
+    }
+}
+
+/// The distance between rows of hexagons that are 1 wide
+const HEX_ROW: f64 = 0.866_025_403_784_438_6;
+
+/// Finds the hexagon that `(u, v)` falls in, measured in bin sizes.
+/// The hexagons are 1 wide with pointy tops. Even rows have hexagons centered on whole numbers,
+/// and odd rows are shifted half a hexagon to the right.
+fn hexagon_key(u: f64, v: f64) -> (i64, i64) {
+    // the even rows and the odd rows are each a rectangular grid of centers, so the nearest center of each
+    // is found by rounding, and the nearer of those two is the hexagon the point is in
+    let even = (u.round(), (v / (2.0 * HEX_ROW)).round() * 2.0);
+    let odd = ((u - 0.5).round() + 0.5, ((v / HEX_ROW - 1.0) / 2.0).round() * 2.0 + 1.0);
+    let distance = |(cu, row): (f64, f64)| (u - cu).powi(2) + (v - row * HEX_ROW).powi(2);
+    if distance(even) <= distance(odd) {
+        (even.0 as i64, even.1 as i64)
+    } else {
+        ((odd.0 - 0.5) as i64, odd.1 as i64)
+    }
+}
+
+/// The center of a hexagon from `hexagon_key`, measured in bin sizes
+fn hexagon_center((i, row): (i64, i64)) -> (f64, f64) {
+    let shift = if row % 2 != 0 { 0.5 } else { 0.0 };
+    (i as f64 + shift, row as f64 * HEX_ROW)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use draw::Range;
+
+    #[test]
+    fn bin_test() {
+        let view = Range2d(Range { min: 0.0, max: 4.0 }, Range { min: 0.0, max: 4.0 });
+        let mut density = Density::hist2d(vec![(0.5, 0.5), (0.6, 0.9), (3.5, 3.5), (f64::NAN, 1.0), (100.0, 1.0)]);
+        density.bins = 4;
+
+        let bins = density.bin(view);
+        assert_eq!(bins.bins.len(), 2);
+        assert_eq!(bins.range, (1.0, 2.0));
+        let full = bins.bins.iter().find(|b| b.count == 2).unwrap();
+        assert_eq!(full.center, (0.5, 0.5));
+
+        density.values = Some(vec![1.0, 3.0, 5.0, 0.0, 0.0]);
+        density.reduce = Reduce::Mean;
+        assert_eq!(density.bin(view).range, (2.0, 5.0));
+
+        density.min_count = 2;
+        assert_eq!(density.bin(view).bins.len(), 1);
+    }
+
+    #[test]
+    fn grid_test() {
+        let mut heatmap = Density::heatmap(vec![vec![1.0, 2.0, 3.0], vec![4.0, f64::NAN, 6.0]]);
+        let all = Range2d(Range { min: -1.0, max: 4.0 }, Range { min: -1.0, max: 3.0 });
+        let bins = heatmap.bin(all);
+        assert_eq!(bins.bins.len(), 5);
+        assert_eq!(bins.range, (1.0, 6.0));
+        assert_eq!(bins.bins[3].center, (0.5, 1.5));
+
+        // only the cells in view, on a grid stretched over a different range
+        heatmap.grid.as_mut().unwrap().x = Range { min: 10.0, max: 16.0 };
+        let bins = heatmap.bin(Range2d(Range { min: 14.5, max: 20.0 }, Range { min: 0.0, max: 1.0 }));
+        assert_eq!(bins.bins.len(), 1);
+        assert_eq!(bins.bins[0].center, (15.0, 0.5));
+        assert_eq!(bins.size, (2.0, 1.0));
+    }
+
+    #[test]
+    fn hexagon_test() {
+        // the centers themselves, and points just inside the pointy top of a hexagon
+        assert_eq!(hexagon_key(2.0, 0.0), (2, 0));
+        assert_eq!(hexagon_key(2.5, HEX_ROW), (2, 1));
+        assert_eq!(hexagon_key(2.0, 0.55), (2, 0));
+        assert_eq!(hexagon_key(2.45, 0.5), (2, 1));
+        assert_eq!(hexagon_center((2, 1)), (2.5, HEX_ROW));
+        assert_eq!(hexagon_center((-1, -1)), (-0.5, -HEX_ROW));
+    }
+}
@@ -0,0 +1,280 @@
+//! **distribution** summarizes groups of raw samples, for box plots and violin plots.
+//!
+//! Box plots show the median, the quartiles and the whiskers of each group, with the samples beyond the whiskers as outliers.
+//! Violin plots show a Gaussian kernel density estimate of each group, mirrored around the group's position.
+
+use std::f64;
+
+/// `Whiskers` decides how far the whiskers of a box plot reach. Samples beyond the whiskers are outliers.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Whiskers {
+    /// Tukey's rule: the whiskers reach the furthest samples within this many interquartile ranges of the box.
+    /// `Tukey(1.5)` is the usual choice.
+    Tukey(f64),
+
+    /// The whiskers reach the smallest and largest samples, so nothing is an outlier
+    MinMax,
+
+    /// The whiskers reach these percentiles of the samples, e.g. `Percentiles(5.0, 95.0)`
+    Percentiles(f64, f64),
+}
+
+/// `BoxStats` is the summary of one group that a box plot draws
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxStats {
+    pub median: f64,
+
+    /// The first quartile, where the box starts
+    pub q1: f64,
+
+    /// The third quartile, where the box ends
+    pub q3: f64,
+
+    pub whisker_low: f64,
+    pub whisker_high: f64,
+
+    /// The samples beyond the whiskers
+    pub outliers: Vec<f64>,
+
+    /// The ends of the notch, a rough 95% confidence interval of the median
+    pub notch_low: f64,
+    pub notch_high: f64,
+}
+
+impl BoxStats {
+    /// `map` applies `f` to every value, e.g. to transform them by an axis scale. `f` should be increasing.
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> BoxStats {
+        BoxStats {
+            median: f(self.median),
+            q1: f(self.q1),
+            q3: f(self.q3),
+            whisker_low: f(self.whisker_low),
+            whisker_high: f(self.whisker_high),
+            outliers: self.outliers.iter().map(|&v| f(v)).collect(),
+            notch_low: f(self.notch_low),
+            notch_high: f(self.notch_high),
+        }
+    }
+}
+
+/// `BoxPlot` describes box-and-whisker plots of several groups of samples, one box per group along a categorical x-axis
+#[derive(Clone, Debug)]
+pub struct BoxPlot {
+    /// **groups** contains the name of each group and its raw samples. Non-finite samples are left out.
+    pub groups: Vec<(String, Vec<f64>)>,
+
+    /// **whiskers** decides how far the whiskers reach
+    pub whiskers: Whiskers,
+
+    /// **notch** narrows the box around the median to show how certain the median is
+    pub notch: bool,
+
+    /// **outliers** draws a mark for each sample beyond the whiskers
+    pub outliers: bool,
+
+    /// **color** fills the boxes
+    pub color: [f32; 4],
+
+    /// **width** of each box, as a fraction of the distance between groups
+    pub width: f64,
+}
+
+impl BoxPlot {
+    /// `new` creates unnotched box plots of the given groups, with Tukey whiskers at 1.5 IQR and the outliers beyond them
+    pub fn new<S: AsRef<str>>(groups: Vec<(S, Vec<f64>)>) -> BoxPlot {
+        BoxPlot {
+            groups: groups.into_iter().map(|(name, samples)| (name.as_ref().to_string(), samples)).collect(),
+            whiskers: Whiskers::Tukey(1.5),
+            notch: false,
+            outliers: true,
+            color: [0.55, 0.7, 0.9, 1.0],
+            width: 0.6,
+        }
+    }
+}
+
+/// `Violin` describes violin plots of several groups of samples, one violin per group along a categorical x-axis
+#[derive(Clone, Debug)]
+pub struct Violin {
+    /// **groups** contains the name of each group and its raw samples. Non-finite samples are left out.
+    pub groups: Vec<(String, Vec<f64>)>,
+
+    /// **bandwidth** optionally sets the standard deviation of the Gaussian kernel.
+    /// If `None`, it is picked for each group with Silverman's rule of thumb.
+    pub bandwidth: Option<f64>,
+
+    /// **points** is the number of points the density is evaluated at along each violin
+    pub points: usize,
+
+    /// **quartiles** draws the quartiles and the median inside each violin
+    pub quartiles: bool,
+
+    /// **color** fills the violins
+    pub color: [f32; 4],
+
+    /// **width** of each violin at its widest, as a fraction of the distance between groups
+    pub width: f64,
+}
+
+impl Violin {
+    /// `new` creates violin plots of the given groups, with an automatic bandwidth and the quartiles marked
+    pub fn new<S: AsRef<str>>(groups: Vec<(S, Vec<f64>)>) -> Violin {
+        Violin {
+            groups: groups.into_iter().map(|(name, samples)| (name.as_ref().to_string(), samples)).collect(),
+            bandwidth: None,
+            points: 100,
+            quartiles: true,
+            color: [0.7, 0.6, 0.85, 1.0],
+            width: 0.8,
+        }
+    }
+}
+
+/// The finite samples, sorted
+fn sorted(samples: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = samples.iter().cloned().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted
+}
+
+/// `quantile` interpolates the `q` quantile, between 0 and 1, of samples that are already sorted
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let pos = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let i = pos.floor() as usize;
+    let frac = pos - i as f64;
+    match sorted.get(i + 1) {
+        Some(&next) => sorted[i] + (next - sorted[i]) * frac,
+        None => sorted[i],
+    }
+}
+
+/// `box_stats` summarizes a group of samples, or returns `None` if none of them are finite
+pub fn box_stats(samples: &[f64], whiskers: Whiskers) -> Option<BoxStats> {
+    let sorted = sorted(samples);
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+    let median = quantile(&sorted, 0.5);
+    let q1 = quantile(&sorted, 0.25);
+    let q3 = quantile(&sorted, 0.75);
+    let iqr = q3 - q1;
+
+    let (whisker_low, whisker_high) = match whiskers {
+        Whiskers::Tukey(k) => {
+            // the whiskers stop at real samples, so they never reach past the data
+            let (low, high) = (q1 - k * iqr, q3 + k * iqr);
+            let whisker_low = sorted.iter().cloned().find(|&v| v >= low).unwrap_or(min);
+            let whisker_high = sorted.iter().cloned().rev().find(|&v| v <= high).unwrap_or(max);
+            (whisker_low.min(q1), whisker_high.max(q3))
+        }
+        Whiskers::MinMax => (min, max),
+        Whiskers::Percentiles(low, high) => (quantile(&sorted, low / 100.0), quantile(&sorted, high / 100.0)),
+    };
+
+    let outliers = sorted.iter().cloned().filter(|&v| v < whisker_low || v > whisker_high).collect();
+
+    let notch = 1.57 * iqr / (sorted.len() as f64).sqrt();
+
+    Some(BoxStats {
+        median,
+        q1,
+        q3,
+        whisker_low,
+        whisker_high,
+        outliers,
+        notch_low: median - notch,
+        notch_high: median + notch,
+    })
+}
+
+/// `silverman_bandwidth` picks a kernel bandwidth for a kernel density estimate of the samples
+pub fn silverman_bandwidth(samples: &[f64]) -> f64 {
+    let sorted = sorted(samples);
+    let n = sorted.len() as f64;
+    if sorted.len() < 2 {
+        return 1.0;
+    }
+
+    let mean = sorted.iter().sum::<f64>() / n;
+    let std_dev = (sorted.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1.0)).sqrt();
+    let iqr = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+
+    // the interquartile range keeps a few far outliers from blurring everything, unless it's zero
+    let spread = if iqr > 0.0 { std_dev.min(iqr / 1.34) } else { std_dev };
+    if spread > 0.0 {
+        0.9 * spread * n.powf(-0.2)
+    } else {
+        1.0
+    }
+}
+
+/// `kde` estimates the density of the samples with a Gaussian kernel of standard deviation `bandwidth`.
+/// Returns `(value, density)` at `points` evenly spaced values from the smallest to the largest sample.
+pub fn kde(samples: &[f64], bandwidth: f64, points: usize) -> Vec<(f64, f64)> {
+    let sorted = sorted(samples);
+    if sorted.is_empty() || bandwidth <= 0.0 {
+        return vec![];
+    }
+
+    let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+    let points = if min == max { 1 } else { points.max(2) };
+    let norm = 1.0 / (sorted.len() as f64 * bandwidth * (2.0 * f64::consts::PI).sqrt());
+
+    (0..points)
+        .map(|i| {
+            let value = if points == 1 { min } else { min + (max - min) * i as f64 / (points - 1) as f64 };
+            let density = sorted
+                .iter()
+                .map(|s| {
+                    let u = (value - s) / bandwidth;
+                    (-0.5 * u * u).exp()
+                })
+                .sum::<f64>() * norm;
+            (value, density)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn box_stats_test() {
+        let mut samples: Vec<f64> = (1..10).map(|v| v as f64).collect();
+        samples.push(100.0);
+        samples.push(f64::NAN);
+
+        let stats = box_stats(&samples, Whiskers::Tukey(1.5)).unwrap();
+        assert_eq!(stats.median, 5.5);
+        assert_eq!((stats.q1, stats.q3), (3.25, 7.75));
+        assert_eq!((stats.whisker_low, stats.whisker_high), (1.0, 9.0));
+        assert_eq!(stats.outliers, vec![100.0]);
+
+        let stats = box_stats(&samples, Whiskers::MinMax).unwrap();
+        assert_eq!((stats.whisker_low, stats.whisker_high), (1.0, 100.0));
+        assert!(stats.outliers.is_empty());
+
+        assert!(box_stats(&[f64::NAN], Whiskers::MinMax).is_none());
+    }
+
+    #[test]
+    fn kde_test() {
+        let samples = [-1.0, 0.0, 0.0, 1.0];
+        let density = kde(&samples, 0.5, 21);
+        assert_eq!(density.len(), 21);
+        assert_eq!(density[0].0, -1.0);
+        assert_eq!(density[20].0, 1.0);
+
+        // symmetric, and highest in the middle
+        assert!((density[5].1 - density[15].1).abs() < 1e-12);
+        assert!(density.iter().all(|d| d.1 <= density[10].1));
+
+        assert!(silverman_bandwidth(&samples) > 0.0);
+    }
+}
@@ -1,5 +1,9 @@
 #![allow(dead_code)]
 
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
 #[derive(Copy, Clone, Debug)]
 pub enum MouseButton {
     Left,
@@ -7,6 +11,7 @@ pub enum MouseButton {
     Right,
 }
 
+/// Mouse positions are in pixels from the top left of the window
 #[derive(Copy, Clone, Debug)]
 pub enum Event {
     Quit,
@@ -15,8 +20,59 @@ pub enum Event {
     KeyUp(i32),
     MouseDown(MouseButton, f64, f64),
     MouseUp(MouseButton, f64, f64),
-    MouseMove(MouseButton, f64, f64),
+
+    /// The mouse moved, possibly while a button was held down
+    MouseMove(Option<MouseButton>, f64, f64),
     MouseScroll(i32, i32),
+
+    /// Another thread asked for the plot to be looked at again, through a `Waker`
+    Wake,
+}
+
+/// How often the default `Drawable::wait_events` checks for events
+pub const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// `Waker` makes a `Drawable` waiting in `wait_events` stop waiting, from any thread,
+/// so that a plot notices changes that don't come from its own window
+#[derive(Clone)]
+pub struct Waker(Arc<dyn Fn() + Send + Sync>);
+
+impl Waker {
+    /// `new` creates a waker that calls `wake` to wake the `Drawable` up
+    pub fn new<F: Fn() + Send + Sync + 'static>(wake: F) -> Waker {
+        Waker(Arc::new(wake))
+    }
+
+    /// `wake` makes the `Drawable` stop waiting, and return `Event::Wake` from `wait_events`
+    pub fn wake(&self) {
+        (self.0)();
+    }
+}
+
+/// Horizontal alignment of text relative to the point it is drawn at
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// Vertical alignment of text relative to the point it is drawn at
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// `Quality` decides how a `Drawable` draws lines
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Quality {
+    /// Lines are drawn a whole pixel at a time, which is fastest but leaves diagonal lines jagged
+    Fast,
+
+    /// Pixels along the edges of lines are blended with what is behind them by how much of them the line covers
+    AntiAliased,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -57,9 +113,24 @@ pub trait Drawable: Send {
     /// Gets the visible range of worldspace
     fn get_view(&self) -> Range2d;
 
+    /// Gets the size of the output surface in pixels.
+    /// By default it is the size of the view, as if each unit of worldspace were a pixel.
+    fn get_size(&self) -> (f64, f64) {
+        let view = self.get_view();
+        (view.0.size().abs(), view.1.size().abs())
+    }
+
     /// Set color for various drawing actions
     fn set_color(&mut self, color: [u8; 4]);
 
+    /// Sets how lines are drawn. Backends that can only draw one way may ignore it.
+    fn set_quality(&mut self, _quality: Quality) {}
+
+    /// Gets how lines are drawn
+    fn get_quality(&self) -> Quality {
+        Quality::Fast
+    }
+
     /// Clears the output surface
     fn clear(&mut self);
 
@@ -75,18 +146,332 @@ pub trait Drawable: Send {
     /// Draws a rectangle bounded by two corners
     fn unfilled_rectangle(&mut self, a: (f64, f64), b: (f64, f64));
 
+    /// Draws a filled polygon through the given points in worldspace.
+    /// By default it is filled with a `rectangle` for each row of pixels from `polygon_spans`.
+    fn polygon(&mut self, points: &[(f64, f64)]) {
+        let view = self.get_view();
+        let (w, h) = self.get_size();
+        if view.0.size() == 0.0 || view.1.size() == 0.0 || w <= 0.0 || h <= 0.0 {
+            return;
+        }
+
+        let pixels: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| ((x - view.0.min) / view.0.size() * w, (view.1.max - y) / view.1.size() * h))
+            .collect();
+        let x = |px: i32| view.0.min + px as f64 / w * view.0.size();
+        let y = |py: i32| view.1.max - py as f64 / h * view.1.size();
+        for (row, start, end) in polygon_spans(&pixels) {
+            self.rectangle((x(start), y(row)), (x(end), y(row + 1)));
+        }
+    }
+
+    /// Draws connected lines through the given points in worldspace, so backends can join them up
+    /// and keep their output compact. By default each piece is drawn with `thick_line`.
+    fn polyline(&mut self, points: &[(f64, f64)], thickness: u16) {
+        for pair in points.windows(2) {
+            self.thick_line(pair[0], pair[1], thickness);
+        }
+    }
+
+    /// Draws the edges of a polygon through the given points in worldspace, back around to the first point.
+    /// By default it is drawn with `polyline`.
+    fn unfilled_polygon(&mut self, points: &[(f64, f64)]) {
+        if let Some(&first) = points.first() {
+            let mut closed = points.to_vec();
+            closed.push(first);
+            self.polyline(&closed, 1);
+        }
+    }
+
+    /// Draws a filled wedge of the ring between radii `inner` and `outer` around `center` in worldspace,
+    /// from the angle `start` to `end` in radians counterclockwise from the right. An `inner` radius of 0 draws a pie slice.
+    /// The radii are in worldspace, so circles are only round when the view has the same scale along both axes.
+    /// By default the curved edges are made of straight pieces and drawn with `polygon`.
+    fn wedge(&mut self, center: (f64, f64), inner: f64, outer: f64, start: f64, end: f64) {
+        let mut points = arc_points(center, outer, start, end);
+        if inner > 0.0 {
+            points.extend(arc_points(center, inner, end, start));
+        } else {
+            points.push(center);
+        }
+        self.polygon(&points);
+    }
+
+    /// Draws the arc of the circle of `radius` around `center` in worldspace, from the angle `start` to `end`
+    /// in radians counterclockwise from the right. By default the arc is made of straight pieces and drawn with `polyline`.
+    fn arc(&mut self, center: (f64, f64), radius: f64, start: f64, end: f64) {
+        self.polyline(&arc_points(center, radius, start, end), 1);
+    }
+
+    /// Draws text at (x, y) in worldspace, aligned relative to that point.
+    /// Backends without text support may leave this empty.
+    fn text(&mut self, _pos: (f64, f64), _text: &str, _halign: HAlign, _valign: VAlign) {}
+
+    /// Draws text turned a quarter turn counterclockwise, so it reads from bottom to top, at (x, y) in worldspace.
+    /// The alignment applies to the turned text. Backends that can't turn text may draw it like `text` does.
+    fn vertical_text(&mut self, pos: (f64, f64), text: &str, halign: HAlign, valign: VAlign) {
+        self.text(pos, text, halign, valign);
+    }
+
+    /// Measures text in pixels, as `text` would draw it.
+    /// Backends without text support may leave this returning (0, 0).
+    fn text_size(&self, _text: &str) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// Keeps everything drawn since the last `clear` as a layer, so that `restore_layer` can bring it back
+    /// without drawing it all again. Backends that can't keep a layer may leave this empty.
+    fn cache_layer(&mut self) {}
+
+    /// Starts a new frame from the layer kept by `cache_layer`, to draw overlays on top of.
+    /// Returns false if there is no layer to start from, in which case the frame has to be drawn from scratch.
+    fn restore_layer(&mut self) -> bool {
+        false
+    }
+
     /// Presents the previously drawn buffer
     fn present(&mut self);
 
     /// Returns the next pending events
     fn get_events(&mut self) -> Vec<Event>;
 
+    /// Waits until there are events and returns them, or returns no events once `timeout` has passed.
+    /// With no timeout it waits for as long as it takes. By default it checks `get_events` every `POLL_INTERVAL`,
+    /// but backends that can sleep until something happens should, so that idle plots use no CPU.
+    fn wait_events(&mut self, timeout: Option<Duration>) -> Vec<Event> {
+        let start = Instant::now();
+        loop {
+            let events = self.get_events();
+            if !events.is_empty() || timeout.is_some_and(|t| start.elapsed() >= t) {
+                return events;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Returns a `Waker` that other threads can use to cut `wait_events` short,
+    /// or `None` if the backend can't be woken and only notices changes when `wait_events` times out
+    fn waker(&self) -> Option<Waker> {
+        None
+    }
+
     /// Asks that the Drawable stop any tasks and cleanup
     fn close(&mut self) {} // provide empty default impl
 }
 
+/// The number of straight pieces that make up a full circle in `arc_points`
+pub const ARC_SEGMENTS: usize = 128;
+
+/// Computes points along the arc of the circle of `radius` around `center`, from the angle `start` to `end`
+/// in radians counterclockwise from the right. Both ends are included.
+pub fn arc_points(center: (f64, f64), radius: f64, start: f64, end: f64) -> Vec<(f64, f64)> {
+    let turn = 2.0 * ::std::f64::consts::PI;
+    let steps = ((end - start).abs() / turn * ARC_SEGMENTS as f64).ceil().max(1.0) as usize;
+    (0..steps + 1)
+        .map(|i| {
+            let angle = start + (end - start) * i as f64 / steps as f64;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+/// Computes how much of each pixel a line `thickness` pixels wide through `points` covers, from 0 to 1,
+/// for software rasterizers to draw anti-aliased lines with. The points must already be in window coordinates.
+/// Each pixel comes up once, so the joins between pieces aren't blended twice.
+pub fn polyline_coverage(points: &[(f64, f64)], thickness: f64) -> Vec<(i32, i32, f64)> {
+    Coverage::new().polyline(points, thickness).to_vec()
+}
+
+/// `Coverage` works out anti-aliased lines like `polyline_coverage`, keeping its buffer from one line to the next
+/// so that drawing a frame doesn't allocate for every line.
+pub struct Coverage {
+    /// **pixels** Every pixel of the last line, in rows from the top, with how much of it is covered
+    pixels: Vec<(i32, i32, f64)>,
+}
+
+impl Coverage {
+    pub fn new() -> Coverage {
+        Coverage { pixels: Vec::new() }
+    }
+
+    /// Computes how much of each pixel a line `thickness` pixels wide through `points` covers, as
+    /// `polyline_coverage` does, into the buffer kept from the last line
+    pub fn polyline(&mut self, points: &[(f64, f64)], thickness: f64) -> &[(i32, i32, f64)] {
+        self.pixels.clear();
+        self.add_pieces(points, thickness.max(1.0) / 2.0);
+
+        // pixels near the joins come up once for each piece, so keep only the most each is covered
+        self.pixels.sort_unstable_by_key(|&(x, y, _)| (y, x));
+        self.pixels.dedup_by(|later, kept| {
+            let same = (later.0, later.1) == (kept.0, kept.1);
+            if same {
+                kept.2 = kept.2.max(later.2);
+            }
+            same
+        });
+        &self.pixels
+    }
+
+    /// Adds the pixels each straight piece of the line covers, some of them more than once
+    fn add_pieces(&mut self, points: &[(f64, f64)], radius: f64) {
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if ![a.0, a.1, b.0, b.1].iter().all(|v| v.is_finite() && v.abs() < 1e6) {
+                continue;
+            }
+
+            // walk along whichever axis the piece is longer in, covering the pixels near it in each column or row
+            let steep = (b.1 - a.1).abs() > (b.0 - a.0).abs();
+            let (a, b) = if steep { ((a.1, a.0), (b.1, b.0)) } else { (a, b) };
+            let (a, b) = if a.0 > b.0 { (b, a) } else { (a, b) };
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length = dx.hypot(dy);
+
+            // far enough across the line to reach every pixel it touches, even where it is at 45 degrees
+            let reach = (radius + 1.0) * if dx > 0.0 { length / dx } else { 1.0 };
+            let start = (a.0 - radius - 1.0).floor() as i32;
+            let end = (b.0 + radius + 1.0).ceil() as i32;
+            for major in start..end {
+                let center = major as f64 + 0.5;
+                let along = if dx > 0.0 { ((center - a.0) / dx).clamp(0.0, 1.0) } else { 0.0 };
+                let middle = a.1 + along * dy;
+                for minor in (middle - reach).floor() as i32..(middle + reach).ceil() as i32 {
+                    let p = (center, minor as f64 + 0.5);
+
+                    // the distance from the pixel's center to the nearest point of the piece
+                    let t = if length > 0.0 { (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / (length * length)).clamp(0.0, 1.0) } else { 0.0 };
+                    let distance = (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy));
+                    let covered = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                    if covered > 0.0 {
+                        let (x, y) = if steep { (minor, major) } else { (major, minor) };
+                        self.pixels.push((x, y, covered));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for Coverage {
+    fn default() -> Coverage {
+        Coverage::new()
+    }
+}
+
 pub fn point2window(pt: f64, view: Range, window: Range, invert: bool) -> f64 {
     let moved_pt = if invert { view.max - pt } else { pt - view.min };
 
     (moved_pt / view.size()) * (window.size() + window.min)
-}
\ No newline at end of file
+}
+
+/// Computes the horizontal spans `(y, x_start, x_end)` of pixels whose centers lie inside a polygon.
+/// The points must already be in window coordinates. Spans are half-open, so polygons sharing an edge
+/// tile without gaps or overlap.
+pub fn polygon_spans(points: &[(f64, f64)]) -> Vec<(i32, i32, i32)> {
+    let mut spans = Vec::new();
+    if points.len() < 3 {
+        return spans;
+    }
+
+    let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    if !min_y.is_finite() || !max_y.is_finite() {
+        return spans;
+    }
+
+    let mut crossings = Vec::new();
+    for row in (min_y.floor() as i32)..(max_y.ceil() as i32) {
+        let yc = row as f64 + 0.5;
+        crossings.clear();
+        for i in 0..points.len() {
+            let (x0, y0) = points[i];
+            let (x1, y1) = points[(i + 1) % points.len()];
+            if (y0 <= yc && yc < y1) || (y1 <= yc && yc < y0) {
+                crossings.push(x0 + (yc - y0) * (x1 - x0) / (y1 - y0));
+            }
+        }
+        crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for pair in crossings.chunks(2) {
+            if pair.len() == 2 {
+                let start = (pair[0] - 0.5).ceil() as i32;
+                let end = (pair[1] - 0.5).ceil() as i32;
+                if end > start {
+                    spans.push((row, start, end));
+                }
+            }
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Keeps the rectangles drawn on a 10 by 10 pixel surface, showing the same range of worldspace
+    struct Rectangles {
+        view: Range2d,
+        drawn: Vec<((f64, f64), (f64, f64))>,
+    }
+
+    impl Drawable for Rectangles {
+        fn set_view(&mut self, view: Range2d) {
+            self.view = view;
+        }
+        fn get_view(&self) -> Range2d {
+            self.view
+        }
+        fn get_size(&self) -> (f64, f64) {
+            (10.0, 10.0)
+        }
+        fn set_color(&mut self, _color: [u8; 4]) {}
+        fn clear(&mut self) {}
+        fn line(&mut self, _a: (f64, f64), _b: (f64, f64)) {}
+        fn thick_line(&mut self, _a: (f64, f64), _b: (f64, f64), _thickness: u16) {}
+        fn rectangle(&mut self, a: (f64, f64), b: (f64, f64)) {
+            self.drawn.push((a, b));
+        }
+        fn unfilled_rectangle(&mut self, _a: (f64, f64), _b: (f64, f64)) {}
+        fn present(&mut self) {}
+        fn get_events(&mut self) -> Vec<Event> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn polygon_test() {
+        // by default a polygon is filled with a rectangle for each row of pixels it covers
+        let pixels = Range { min: 0.0, max: 10.0 };
+        let mut renderer = Rectangles { view: Range2d(pixels, pixels), drawn: Vec::new() };
+        renderer.polygon(&[(2.0, 2.0), (5.0, 2.0), (5.0, 6.0), (2.0, 6.0)]);
+        assert_eq!(renderer.drawn.len(), 4);
+        let area: f64 = renderer.drawn.iter().map(|&(a, b)| ((b.0 - a.0) * (a.1 - b.1)).abs()).sum();
+        assert_eq!(area, 12.0);
+    }
+
+    #[test]
+    fn coverage_test() {
+        // a flat line along the middle of a row of pixels covers that row and fades out on either side
+        let covered = polyline_coverage(&[(0.0, 5.5), (10.0, 5.5)], 1.0);
+        let at = |x: i32, y: i32| covered.iter().find(|c| c.0 == x && c.1 == y).map_or(0.0, |c| c.2);
+        assert_eq!(at(5, 5), 1.0);
+        assert_eq!(at(5, 4), 0.0);
+        assert_eq!(at(5, 6), 0.0);
+
+        // halfway between two rows, each is half covered
+        let covered = polyline_coverage(&[(0.0, 5.0), (10.0, 5.0)], 1.0);
+        let at = |x: i32, y: i32| covered.iter().find(|c| c.0 == x && c.1 == y).map_or(0.0, |c| c.2);
+        assert_eq!((at(5, 4), at(5, 5)), (0.5, 0.5));
+
+        // the join between two pieces is only counted once
+        let covered = polyline_coverage(&[(0.0, 0.5), (5.5, 0.5), (5.5, 10.0)], 1.0);
+        assert_eq!(covered.iter().filter(|c| c.0 == 5 && c.1 == 0).count(), 1);
+
+        // a reused buffer forgets the last line
+        let mut coverage = Coverage::new();
+        coverage.polyline(&[(0.0, 0.0), (50.0, 50.0)], 3.0);
+        assert_eq!(coverage.polyline(&[(0.0, 0.5), (5.5, 0.5), (5.5, 10.0)], 1.0), &covered[..]);
+    }
+}
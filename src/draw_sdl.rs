@@ -4,23 +4,279 @@
 use sdl2_mt;
 use sdl2_mt::event::Event as SdlEvent;
 use sdl2_mt::pixels;
+use sdl2_mt::rect::{Point, Rect};
+use sdl2_mt::render::BlendMode;
 
 use sdl2_mt::Sdl2Mt;
 
+use std::mem;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
 use draw::*;
+use font;
+
+/// How many screen pixels each font pixel is drawn as
+const TEXT_SCALE: u32 = 2;
+
+/// How many steps of coverage anti-aliased lines are blended with, each drawn in one go
+const COVERAGE_LEVELS: usize = 16;
+
+/// Marks the SDL user events sent by a `Waker`
+const WAKE_CODE: i32 = 0x706c_6f74;
+
+/// Pushes an event that wakes up the window `window_id`. SDL allows this from any thread.
+fn push_wake(window_id: u32) {
+    let wake = sdl2_mt::sys::event::SDL_UserEvent {
+        type_: sdl2_mt::sys::event::SDL_USEREVENT,
+        timestamp: 0,
+        windowID: window_id,
+        code: WAKE_CODE,
+        data1: ptr::null_mut(),
+        data2: ptr::null_mut(),
+    };
+    let mut event = sdl2_mt::sys::event::SDL_Event { data: [0; 56] };
+
+    // a user event fits in the bytes of any event, and SDL copies it into its queue
+    unsafe {
+        ptr::write(event.data.as_mut_ptr() as *mut sdl2_mt::sys::event::SDL_UserEvent, wake);
+        sdl2_mt::sys::event::SDL_PushEvent(&mut event);
+    }
+}
+
+/// The longest the UI thread waits in SDL for events, in milliseconds, before it looks again at whether any plot is open
+const IDLE_WAIT: c_int = 1000;
+
+/// The plot threads waiting for events, each with the window it waits on.
+/// Every window shares the UI thread, so the plot threads wait on these signals instead of on the UI thread.
+struct Watchers {
+    signals: Vec<(u32, Sender<()>)>,
+
+    /// Whether the UI thread is waiting in SDL for events, or has been asked to
+    waiting: bool,
+
+    /// Whether `watch_event` has been added to SDL
+    watching: bool,
+
+    /// The SDL event type that cuts the UI thread's wait short, or 0 until the UI thread has registered it
+    nudge: u32,
+}
+
+static WATCHERS: Mutex<Watchers> = Mutex::new(Watchers {
+    signals: Vec::new(),
+    waiting: false,
+    watching: false,
+    nudge: 0,
+});
+
+/// Called by SDL for every event as it is queued, on whichever thread queues it.
+/// Signals the plot waiting on the event's window, or every plot when the program is asked to quit.
+extern "C" fn watch_event(_userdata: *mut c_void, event: *mut sdl2_mt::sys::event::SDL_Event) -> c_int {
+    // window, keyboard, mouse and user events all keep their window id after the type and timestamp.
+    // Other events may look like they are for some window, which only wakes it for nothing.
+    let data = unsafe { &(*event).data };
+    let kind = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+    let window_id = u32::from_ne_bytes([data[8], data[9], data[10], data[11]]);
+
+    if let Ok(watchers) = WATCHERS.lock() {
+        for &(id, ref signal) in &watchers.signals {
+            if id == window_id || kind == sdl2_mt::sys::event::SDL_QUIT {
+                let _ = signal.send(());
+            }
+        }
+    }
+    0
+}
 
-/// Provides an SDL2-based interactive plotting backend
+/// Starts signalling the plot of `window_id` whenever its window gets an event
+fn watch(sdlh: &Sdl2Mt, window_id: u32) -> Receiver<()> {
+    let (tx, rx) = channel();
+    let mut watchers = WATCHERS.lock().unwrap();
+    watchers.signals.push((window_id, tx));
+
+    if !watchers.watching {
+        watchers.watching = true;
+        let _ = sdlh.run_on_ui_thread(Box::new(|_sdl, _windows| {
+            let nudge = unsafe {
+                sdl2_mt::sys::event::SDL_AddEventWatch(watch_event, ptr::null_mut());
+                sdl2_mt::sys::event::SDL_RegisterEvents(1)
+            };
+            // SDL hands out all ones when it has no event types left, and then nothing can be nudged
+            if let Ok(mut watchers) = WATCHERS.lock() {
+                watchers.nudge = if nudge == u32::MAX { 0 } else { nudge };
+            }
+        }));
+    }
+    start_waiting(sdlh, &mut watchers);
+    rx
+}
+
+/// Stops signalling the plot of `window_id`
+fn unwatch(window_id: u32) {
+    if let Ok(mut watchers) = WATCHERS.lock() {
+        watchers.signals.retain(|&(id, _)| id != window_id);
+    }
+    nudge();
+}
+
+/// Sets the UI thread waiting for events, unless it already is
+fn start_waiting(sdlh: &Sdl2Mt, watchers: &mut Watchers) {
+    if !watchers.waiting && !watchers.signals.is_empty() {
+        let handle = sdlh.clone();
+        watchers.waiting = sdlh.run_on_ui_thread(Box::new(move |_sdl, _windows| wait(&handle, IDLE_WAIT))).is_ok();
+    }
+}
+
+/// Runs on the UI thread, and sleeps in SDL until the system has events for it, a plot is woken, or `nudge` is called.
+/// Then it goes to the back of the UI thread's queue, so that the work sent meanwhile is done before it waits again.
+/// SDL 2.0.16 and later sleep properly here, so idle plots use no CPU, while older versions poll inside the wait.
+fn wait(sdlh: &Sdl2Mt, timeout: c_int) {
+    use sdl2_mt::sys::event::{SDL_FlushEvent, SDL_HasEvent, SDL_HasEvents, SDL_WaitEventTimeout, SDL_FIRSTEVENT, SDL_LASTEVENT};
+
+    // SDL calls `watch_event` while it waits, so the watchers can't be held until it is done
+    unsafe { SDL_WaitEventTimeout(ptr::null_mut(), timeout) };
+
+    let mut watchers = WATCHERS.lock().unwrap();
+    let nudged = watchers.nudge != 0 && unsafe { SDL_HasEvent(watchers.nudge) } != 0;
+    if nudged {
+        unsafe { SDL_FlushEvent(watchers.nudge) };
+    }
+    if watchers.signals.is_empty() {
+        watchers.waiting = false;
+        return;
+    }
+
+    // events left in SDL would end every wait at once, so the plots are asked to take them.
+    // Taking them with `get_events` sets the UI thread waiting again.
+    if unsafe { SDL_HasEvents(SDL_FIRSTEVENT, SDL_LASTEVENT) } != 0 {
+        for (_, signal) in &watchers.signals {
+            let _ = signal.send(());
+        }
+        watchers.waiting = false;
+        return;
+    }
+
+    // more work often follows a nudge, and may have been sent just after this wait ended,
+    // so the next wait is kept short
+    let next = if nudged { POLL_INTERVAL.as_millis() as c_int } else { IDLE_WAIT };
+    let handle = sdlh.clone();
+    watchers.waiting = sdlh.run_on_ui_thread(Box::new(move |_sdl, _windows| wait(&handle, next))).is_ok();
+}
+
+/// Cuts the UI thread's wait for events short, so that it gets on with the work sent to it.
+/// SDL allows this from any thread.
+fn nudge() {
+    let nudge = match WATCHERS.lock() {
+        Ok(ref watchers) if watchers.waiting => watchers.nudge,
+        _ => 0,
+    };
+    if nudge == 0 {
+        return;
+    }
+
+    let mut event = sdl2_mt::sys::event::SDL_Event { data: [0; 56] };
+    event.data[..4].copy_from_slice(&nudge.to_ne_bytes());
+    unsafe {
+        sdl2_mt::sys::event::SDL_PushEvent(&mut event);
+    }
+}
+
+fn convert_button(button: sdl2_mt::mouse::MouseButton) -> Option<MouseButton> {
+    match button {
+        sdl2_mt::mouse::MouseButton::Left => Some(MouseButton::Left),
+        sdl2_mt::mouse::MouseButton::Middle => Some(MouseButton::Middle),
+        sdl2_mt::mouse::MouseButton::Right => Some(MouseButton::Right),
+        _ => None,
+    }
+}
+
+/// `Command` is a drawing call in window coordinates, waiting to be sent to the UI thread with the rest of its frame
+enum Command {
+    Clear(pixels::Color),
+
+    /// Lines joining each point to the next
+    Lines(pixels::Color, Vec<Point>),
+    FillRects(pixels::Color, Vec<Rect>),
+    DrawRects(pixels::Color, Vec<Rect>),
+
+    /// Single pixels, blended with what is behind them by the color's alpha
+    BlendPoints(pixels::Color, Vec<Point>),
+
+    /// A cached layer, drawn again without sending it over again
+    Layer(Arc<Vec<Command>>),
+    Present,
+}
+
+impl Command {
+    fn run(&self, canvas: &mut sdl2_mt::render::Canvas<sdl2_mt::video::Window>) {
+        match *self {
+            Command::Clear(color) => {
+                canvas.set_draw_color(color);
+                canvas.clear();
+            }
+            Command::Lines(color, ref points) => {
+                canvas.set_draw_color(color);
+                canvas.draw_lines(&points[..]).unwrap();
+            }
+            Command::FillRects(color, ref rects) => {
+                canvas.set_draw_color(color);
+                canvas.fill_rects(rects).unwrap();
+            }
+            Command::DrawRects(color, ref rects) => {
+                canvas.set_draw_color(color);
+                canvas.draw_rects(rects).unwrap();
+            }
+            Command::BlendPoints(color, ref points) => {
+                canvas.set_blend_mode(BlendMode::Blend);
+                canvas.set_draw_color(color);
+                canvas.draw_points(&points[..]).unwrap();
+                canvas.set_blend_mode(BlendMode::None);
+            }
+            Command::Layer(ref commands) => {
+                for command in commands.iter() {
+                    command.run(canvas);
+                }
+            }
+            Command::Present => canvas.present(),
+        }
+    }
+}
+
+/// Provides an SDL2-based interactive plotting backend.
+/// Each frame is recorded and handed to the UI thread all at once when it is presented.
 pub struct DrawSDL {
     sdlh: Sdl2Mt,
     window_id: u32,
     screenspace: Range2d,
     realspace: Range2d,
     color: pixels::Color,
+    quality: Quality,
+
+    /// Reused for every anti-aliased line, so drawing them doesn't allocate a buffer each time
+    coverage: Coverage,
+
+    /// The frame so far
+    commands: Vec<Command>,
+
+    /// What was drawn before the overlays, kept by `cache_layer`
+    layer: Option<Arc<Vec<Command>>>,
+
+    /// Whether to wait for `present` before sending the frame to the UI thread
+    batched: bool,
+
+    /// Signalled whenever this window gets an event
+    signal: Receiver<()>,
 }
 
 impl DrawSDL {
     pub fn new(sdlh: Sdl2Mt) -> Box<DrawSDL> {
+        // the UI thread may be waiting for events on behalf of other windows
+        nudge();
         let window_id = sdlh.create_simple_window("2D plot", 720, 720).unwrap();
+        let signal = watch(&sdlh, window_id);
 
         let default_s = Range { min: 0.0, max: 0.0 };
 
@@ -35,8 +291,135 @@ impl DrawSDL {
             screenspace: Range2d(default_s, default_s),
             realspace: Range2d(default_r, default_r),
             color: pixels::Color::RGBA(0, 0, 0, 255),
+            quality: Quality::AntiAliased,
+            coverage: Coverage::new(),
+            commands: Vec::new(),
+            layer: None,
+            batched: true,
+            signal,
         })
     }
+
+    /// `set_batched` turns off batching if `batched` is false, so that every drawing call goes to the UI thread
+    /// as soon as it is made. That is much slower, but can help to see where drawing goes wrong.
+    pub fn set_batched(&mut self, batched: bool) {
+        self.batched = batched;
+        if !batched {
+            self.submit();
+        }
+    }
+}
+
+impl DrawSDL {
+    /// Records a command, joining it onto the last one where they can be drawn together
+    fn record(&mut self, command: Command) {
+        let joined = match (self.commands.last_mut(), &command) {
+            (Some(&mut Command::Lines(color, ref mut points)), &Command::Lines(c, ref more)) if color == c && points.last() == more.first() => {
+                points.extend_from_slice(&more[1..]);
+                true
+            }
+            (Some(&mut Command::BlendPoints(color, ref mut points)), &Command::BlendPoints(c, ref more)) if color == c => {
+                points.extend_from_slice(more);
+                true
+            }
+            (Some(&mut Command::FillRects(color, ref mut rects)), &Command::FillRects(c, ref more)) |
+            (Some(&mut Command::DrawRects(color, ref mut rects)), &Command::DrawRects(c, ref more)) if color == c => {
+                rects.extend_from_slice(more);
+                true
+            }
+            _ => false,
+        };
+        if !joined {
+            self.commands.push(command);
+        }
+        if !self.batched {
+            self.submit();
+        }
+    }
+
+    /// Sends the recorded commands to the UI thread in one go
+    fn submit(&mut self) {
+        if self.commands.is_empty() {
+            return;
+        }
+
+        let window_id = self.window_id;
+        let commands = mem::take(&mut self.commands);
+        self.sdlh
+            .run_on_ui_thread(Box::new(move |_sdl, windows| {
+                let canvas = windows.get_mut(&window_id).unwrap();
+                for command in &commands {
+                    command.run(canvas);
+                }
+            }))
+            .unwrap();
+        nudge();
+    }
+
+    /// Draws a line through points in window coordinates, blending its edges, one command per level of coverage
+    fn smooth_polyline(&mut self, points: &[(f64, f64)], thickness: u16) {
+        let mut levels = vec![Vec::new(); COVERAGE_LEVELS];
+        for &(x, y, covered) in self.coverage.polyline(points, thickness as f64) {
+            let level = (covered * COVERAGE_LEVELS as f64).ceil() as usize;
+            levels[level.clamp(1, COVERAGE_LEVELS) - 1].push(Point::new(x, y));
+        }
+
+        let color = self.color;
+        for (i, points) in levels.into_iter().enumerate() {
+            if !points.is_empty() {
+                let alpha = color.a as f64 * (i + 1) as f64 / COVERAGE_LEVELS as f64;
+                let blended = pixels::Color::RGBA(color.r, color.g, color.b, alpha.round() as u8);
+                self.record(Command::BlendPoints(blended, points));
+            }
+        }
+    }
+
+    /// Rasterizes text with the built-in font, optionally turned to read from bottom to top
+    fn draw_text(&mut self, (x, y): (f64, f64), text: &str, halign: HAlign, valign: VAlign, vertical: bool) {
+
+        let x = point2window(x, self.screenspace.0, self.realspace.0, false);
+        let y = point2window(y, self.screenspace.1, self.realspace.1, true);
+
+        let (text_w, text_h) = font::text_size(text);
+        let (w, h) = if vertical { (text_h, text_w) } else { (text_w, text_h) };
+        let (w, h) = ((w * TEXT_SCALE) as f64, (h * TEXT_SCALE) as f64);
+
+        let left = match halign {
+            HAlign::Left => x,
+            HAlign::Center => x - w / 2.0,
+            HAlign::Right => x - w,
+        } as i32;
+        let top = match valign {
+            VAlign::Top => y,
+            VAlign::Center => y - h / 2.0,
+            VAlign::Bottom => y - h,
+        } as i32;
+
+        let mut rects = Vec::new();
+        font::rasterize(text, |px, py| {
+            // turning counterclockwise puts the start of the text at the bottom
+            let (px, py) = if vertical { (py, text_w - 1 - px) } else { (px, py) };
+            rects.push(Rect::new(
+                left + (px * TEXT_SCALE) as i32,
+                top + (py * TEXT_SCALE) as i32,
+                TEXT_SCALE,
+                TEXT_SCALE,
+            ));
+        });
+
+        if rects.is_empty() {
+            return;
+        }
+
+        let color = self.color;
+        self.record(Command::FillRects(color, rects));
+    }
+}
+
+impl Drop for DrawSDL {
+    fn drop(&mut self) {
+        unwatch(self.window_id);
+    }
 }
 
 impl Drawable for DrawSDL {
@@ -50,22 +433,33 @@ impl Drawable for DrawSDL {
         self.screenspace
     }
 
+    /// Gets the size of the output surface in pixels
+    fn get_size(&self) -> (f64, f64) {
+        (self.realspace.0.size(), self.realspace.1.size())
+    }
+
     /// Set color for various drawing actions
     fn set_color(&mut self, color: [u8; 4]) {
         self.color = pixels::Color::RGBA(color[0], color[1], color[2], color[3]);
     }
 
+    /// Sets whether lines are anti-aliased, which they are by default
+    fn set_quality(&mut self, quality: Quality) {
+        self.quality = quality;
+    }
+
+    /// Gets whether lines are anti-aliased
+    fn get_quality(&self) -> Quality {
+        self.quality
+    }
+
     /// Clears the output surface
     fn clear(&mut self) {
-        let window_id = self.window_id;
+        // nothing drawn before the clear would be seen
+        self.commands.clear();
+        self.layer = None;
         let color = self.color;
-        self.sdlh
-            .run_on_ui_thread(Box::new(move |_sdl, windows| {
-                let canvas = windows.get_mut(&window_id).unwrap();
-                canvas.set_draw_color(color);
-                canvas.clear();
-            }))
-            .unwrap();
+        self.record(Command::Clear(color));
     }
 
     /// Draws a line from (x, y) -> (x, y) in worldspace
@@ -75,6 +469,11 @@ impl Drawable for DrawSDL {
 
     /// Draws a line from (x, y) -> (x, y) in worldspace
     fn thick_line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), thickness: u16) {
+        // lines along the axes are left crisp, rather than blurred across two rows of pixels
+        if self.quality == Quality::AntiAliased && x1 != x2 && y1 != y2 {
+            self.polyline(&[(x1, y1), (x2, y2)], thickness);
+            return;
+        }
 
         let x1 = point2window(x1, self.screenspace.0, self.realspace.0, false);
         let y1 = point2window(y1, self.screenspace.1, self.realspace.1, true);
@@ -82,115 +481,233 @@ impl Drawable for DrawSDL {
         let x2 = point2window(x2, self.screenspace.0, self.realspace.0, false);
         let y2 = point2window(y2, self.screenspace.1, self.realspace.1, true);
 
-        let window_id = self.window_id;
         let color = self.color;
-        self.sdlh
-            .run_on_ui_thread(Box::new(move |_sdl, windows| {
-                let canvas = windows.get_mut(&window_id).unwrap();
-                canvas.set_draw_color(color);
-                canvas
-                    .draw_line((x1 as i32, y1 as i32), (x2 as i32, y2 as i32))
-                    .unwrap();
-            }))
-            .unwrap();
+        self.record(Command::Lines(color, vec![Point::new(x1 as i32, y1 as i32), Point::new(x2 as i32, y2 as i32)]));
     }
 
     /// Draws a rectangle bounded by two corners
     fn rectangle(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
 
-        let x1 = point2window(x1, self.screenspace.0, self.realspace.0, false);
-        let y1 = point2window(y1, self.screenspace.1, self.realspace.1, false);
-
-        let x2 = point2window(x2, self.screenspace.0, self.realspace.0, false);
-        let y2 = point2window(y2, self.screenspace.1, self.realspace.1, false);
+        let x1 = point2window(x1, self.screenspace.0, self.realspace.0, false) as i32;
+        let y1 = point2window(y1, self.screenspace.1, self.realspace.1, true) as i32;
 
-        let x1 = x1 as i32;
-        let y1 = y1 as i32;
-        let w = (x2 as i32 - x1) as u32;
-        let h = (y2 as i32 - y1) as u32;
+        let x2 = point2window(x2, self.screenspace.0, self.realspace.0, false) as i32;
+        let y2 = point2window(y2, self.screenspace.1, self.realspace.1, true) as i32;
 
-        use sdl2_mt::rect::Rect;
+        // the corners may come in any order, and y is flipped on screen
+        let w = (x2 - x1).unsigned_abs();
+        let h = (y2 - y1).unsigned_abs();
+        let x1 = x1.min(x2);
+        let y1 = y1.min(y2);
 
-        let window_id = self.window_id;
         let color = self.color;
-        self.sdlh
-            .run_on_ui_thread(Box::new(move |_sdl, windows| {
-                let canvas = windows.get_mut(&window_id).unwrap();
-                canvas.set_draw_color(color);
-                canvas.fill_rect(Rect::new(x1, y1, w, h)).unwrap();
-            }))
-            .unwrap();
+        self.record(Command::FillRects(color, vec![Rect::new(x1, y1, w, h)]));
     }
 
     /// Draws a rectangle bounded by two corners
     fn unfilled_rectangle(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
 
-        let x1 = point2window(x1, self.screenspace.0, self.realspace.0, false);
-        let y1 = point2window(y1, self.screenspace.1, self.realspace.1, false);
+        let x1 = point2window(x1, self.screenspace.0, self.realspace.0, false) as i32;
+        let y1 = point2window(y1, self.screenspace.1, self.realspace.1, true) as i32;
 
-        let x2 = point2window(x2, self.screenspace.0, self.realspace.0, false);
-        let y2 = point2window(y2, self.screenspace.1, self.realspace.1, false);
+        let x2 = point2window(x2, self.screenspace.0, self.realspace.0, false) as i32;
+        let y2 = point2window(y2, self.screenspace.1, self.realspace.1, true) as i32;
 
-        let x1 = x1 as i32;
-        let y1 = y1 as i32;
-        let w = (x2 as i32 - x1) as u32;
-        let h = (y2 as i32 - y1) as u32;
+        // the corners may come in any order, and y is flipped on screen
+        let w = (x2 - x1).unsigned_abs();
+        let h = (y2 - y1).unsigned_abs();
+        let x1 = x1.min(x2);
+        let y1 = y1.min(y2);
 
-        use sdl2_mt::rect::Rect;
+        let color = self.color;
+        self.record(Command::DrawRects(color, vec![Rect::new(x1, y1, w, h)]));
+    }
+
+    /// Draws a filled polygon through the given points in worldspace
+    fn polygon(&mut self, points: &[(f64, f64)]) {
+        let window_points: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| {
+                (
+                    point2window(x, self.screenspace.0, self.realspace.0, false),
+                    point2window(y, self.screenspace.1, self.realspace.1, true),
+                )
+            })
+            .collect();
+
+        let rects: Vec<Rect> = polygon_spans(&window_points)
+            .into_iter()
+            .map(|(y, x1, x2)| Rect::new(x1, y, (x2 - x1) as u32, 1))
+            .collect();
+
+        if rects.is_empty() {
+            return;
+        }
 
-        let window_id = self.window_id;
         let color = self.color;
-        self.sdlh
-            .run_on_ui_thread(Box::new(move |_sdl, windows| {
-                let canvas = windows.get_mut(&window_id).unwrap();
-                canvas.set_draw_color(color);
-                canvas.draw_rect(Rect::new(x1, y1, w, h)).unwrap();
-            }))
-            .unwrap();
+        self.record(Command::FillRects(color, rects));
+    }
+
+    /// Draws connected lines through the given points in worldspace, all in one go
+    fn polyline(&mut self, points: &[(f64, f64)], thickness: u16) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let window_points: Vec<(f64, f64)> = points
+            .iter()
+            .map(|&(x, y)| {
+                (
+                    point2window(x, self.screenspace.0, self.realspace.0, false),
+                    point2window(y, self.screenspace.1, self.realspace.1, true),
+                )
+            })
+            .collect();
+
+        if self.quality == Quality::AntiAliased {
+            self.smooth_polyline(&window_points, thickness);
+            return;
+        }
+
+        let color = self.color;
+        let points = window_points.iter().map(|&(x, y)| Point::new(x as i32, y as i32)).collect();
+        self.record(Command::Lines(color, points));
+    }
+
+    /// Measures text in pixels, as `text` would draw it
+    fn text_size(&self, text: &str) -> (f64, f64) {
+        let (w, h) = font::text_size(text);
+        ((w * TEXT_SCALE) as f64, (h * TEXT_SCALE) as f64)
     }
 
+    /// Draws text at (x, y) in worldspace, aligned relative to that point
+    fn text(&mut self, pos: (f64, f64), text: &str, halign: HAlign, valign: VAlign) {
+        self.draw_text(pos, text, halign, valign, false);
+    }
+
+    /// Draws text reading from bottom to top at (x, y) in worldspace, aligned relative to that point
+    fn vertical_text(&mut self, pos: (f64, f64), text: &str, halign: HAlign, valign: VAlign) {
+        self.draw_text(pos, text, halign, valign, true);
+    }
+
+    /// Keeps the frame so far, so it can be drawn again under new overlays without being recorded again.
+    /// Without batching, the frame has already been sent off and can't be kept.
+    fn cache_layer(&mut self) {
+        if !self.batched {
+            return;
+        }
+
+        let layer = Arc::new(mem::take(&mut self.commands));
+        self.commands.push(Command::Layer(layer.clone()));
+        self.layer = Some(layer);
+    }
+
+    /// Starts a new frame from the kept layer
+    fn restore_layer(&mut self) -> bool {
+        match self.layer {
+            Some(ref layer) => {
+                self.commands.clear();
+                self.commands.push(Command::Layer(layer.clone()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sends the whole frame to the UI thread, and presents it
     fn present(&mut self) {
-        let window_id = self.window_id;
-        self.sdlh
-            .run_on_ui_thread(Box::new(move |_sdl, windows| {
-                let canvas = windows.get_mut(&window_id).unwrap();
-                canvas.present();
-            }))
-            .unwrap();
+        self.commands.push(Command::Present);
+        self.submit();
     }
 
     /// Returns the next pending event
     fn get_events(&mut self) -> Vec<Event> {
-
-        use std::sync::mpsc::channel;
-
         let (tx, rx) = channel();
 
         let window_id = self.window_id;
+        let nudge_type = WATCHERS.lock().map(|watchers| watchers.nudge).unwrap_or(0);
+        nudge();
 
         self.sdlh
             .handle_ui_events(Box::new(move |_sdl, _windows, event| {
                 match event {
                     &SdlEvent::Quit { .. } => tx.send(Event::Quit).unwrap(),
 
-                    &SdlEvent::MouseWheel { x, y, .. } => {
+                    &SdlEvent::MouseWheel { window_id: id, x, y, .. } if id == window_id => {
                         tx.send(Event::MouseScroll(x, y)).unwrap();
                     }
 
+                    &SdlEvent::MouseMotion {
+                        window_id: id,
+                        mousestate,
+                        x,
+                        y,
+                        ..
+                    } if id == window_id => {
+                        let button = if mousestate.left() {
+                            Some(MouseButton::Left)
+                        } else if mousestate.middle() {
+                            Some(MouseButton::Middle)
+                        } else if mousestate.right() {
+                            Some(MouseButton::Right)
+                        } else {
+                            None
+                        };
+                        tx.send(Event::MouseMove(button, x as f64, y as f64)).unwrap();
+                    }
+
+                    &SdlEvent::MouseButtonDown {
+                        window_id: id,
+                        mouse_btn,
+                        x,
+                        y,
+                        ..
+                    } if id == window_id => {
+                        if let Some(button) = convert_button(mouse_btn) {
+                            tx.send(Event::MouseDown(button, x as f64, y as f64)).unwrap();
+                        }
+                    }
+
+                    &SdlEvent::MouseButtonUp {
+                        window_id: id,
+                        mouse_btn,
+                        x,
+                        y,
+                        ..
+                    } if id == window_id => {
+                        if let Some(button) = convert_button(mouse_btn) {
+                            tx.send(Event::MouseUp(button, x as f64, y as f64)).unwrap();
+                        }
+                    }
+
+                    &SdlEvent::User { window_id: id, code: WAKE_CODE, .. } if id == window_id => {
+                        tx.send(Event::Wake).unwrap();
+                    }
+
+                    // nudges have done their job once the UI thread is here, so they are taken by whichever window sees them
+                    &SdlEvent::User { type_, .. } if type_ == nudge_type && nudge_type != 0 => {}
+
                     &SdlEvent::KeyDown {
-                        window_id,
+                        window_id: id,
                         keycode: Some(keycode),
                         ..
-                    } => {
+                    } if id == window_id => {
                         tx.send(Event::KeyDown(keycode as i32)).unwrap();
                     }
                     &SdlEvent::Window {
-                        window_id,
+                        window_id: id,
                         win_event: sdl2_mt::event::WindowEvent::Resized(new_w, new_h),
                         ..
-                    } => {
+                    } if id == window_id => {
                         tx.send(Event::Resize(new_w as f64, new_h as f64)).unwrap();
                     }
+                    &SdlEvent::Window {
+                        window_id: id,
+                        win_event: sdl2_mt::event::WindowEvent::Close,
+                        ..
+                    } if id == window_id => {
+                        tx.send(Event::Quit).unwrap();
+                    }
                     _ => return false,
                 }
                 true
@@ -199,6 +716,11 @@ impl Drawable for DrawSDL {
 
         let events: Vec<_> = rx.iter().collect();
 
+        // SDL's queue is empty again, so the UI thread can go back to waiting for events
+        if let Ok(mut watchers) = WATCHERS.lock() {
+            start_waiting(&self.sdlh, &mut watchers);
+        }
+
         // let's find the last window resize event and save its values
         if let Some(&Event::Resize(w, h)) =
             events.iter().rev().find(|&event| match event {
@@ -211,4 +733,34 @@ impl Drawable for DrawSDL {
 
         events
     }
+
+    /// Sleeps on this window's signal until SDL has events for it, while the UI thread sleeps in SDL for every window
+    fn wait_events(&mut self, timeout: Option<Duration>) -> Vec<Event> {
+        let start = Instant::now();
+        loop {
+            // signals for events that are about to be taken are stale, so they are dropped first
+            while self.signal.try_recv().is_ok() {}
+
+            let events = self.get_events();
+            if !events.is_empty() {
+                return events;
+            }
+            let signalled = match timeout {
+                Some(timeout) => match timeout.checked_sub(start.elapsed()) {
+                    Some(left) if left > Duration::from_millis(0) => self.signal.recv_timeout(left),
+                    _ => return events,
+                },
+                None => self.signal.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            if let Err(RecvTimeoutError::Disconnected) = signalled {
+                return vec![Event::Quit];
+            }
+        }
+    }
+
+    /// Returns a `Waker` that wakes this window's plot from another thread
+    fn waker(&self) -> Option<Waker> {
+        let window_id = self.window_id;
+        Some(Waker::new(move || push_wake(window_id)))
+    }
 }
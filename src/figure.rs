@@ -0,0 +1,123 @@
+//! **figure** arranges several plots in a grid inside a single window.
+//!
+//! Each `PlotBuilder2D` in a `Figure` gets its own axes, with its own view, ticks and labels.
+//! Scrolling or dragging over one of them in an interactive window zooms or pans only that one,
+//! unless its axes are shared with other subplots through `Figure::share_x` or `Figure::share_y`.
+
+use plotbuilder::PlotBuilder2D;
+
+/// `Cell` is the block of grid cells that one subplot covers
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cell {
+    /// **row** is the top row covered, counting from 0
+    pub row: usize,
+
+    /// **col** is the leftmost column covered, counting from 0
+    pub col: usize,
+
+    /// **row_span** is the number of rows covered
+    pub row_span: usize,
+
+    /// **col_span** is the number of columns covered
+    pub col_span: usize,
+}
+
+/// `Subplot` is one set of axes in a `Figure`
+#[derive(Clone)]
+pub struct Subplot {
+    /// **plot_builder** describes what is drawn in the subplot
+    pub plot_builder: PlotBuilder2D,
+
+    /// **cell** is where in the grid the subplot goes
+    pub cell: Cell,
+
+    /// **share_x** is the index of another subplot whose x axis this one shares, if any
+    pub share_x: Option<usize>,
+
+    /// **share_y** is the index of another subplot whose y axis this one shares, if any
+    pub share_y: Option<usize>,
+}
+
+/// `Figure` contains all of the necessary information to draw a grid of 2 dimensional plots in one window
+#[derive(Clone)]
+pub struct Figure {
+    /// **rows** is the number of rows in the grid
+    pub rows: usize,
+
+    /// **cols** is the number of columns in the grid
+    pub cols: usize,
+
+    /// **subplots** contains the plots and where they go. Subplots should not overlap.
+    pub subplots: Vec<Subplot>,
+
+    /// A string to title the whole figure.
+    pub title: Option<String>,
+}
+
+impl Figure {
+    /// `new` creates an empty figure with a grid of `rows` by `cols` cells
+    pub fn new(rows: usize, cols: usize) -> Figure {
+        Figure {
+            rows: rows.max(1),
+            cols: cols.max(1),
+            subplots: vec![],
+            title: None,
+        }
+    }
+
+    /// `add` places a plot in the cell at `row`, `col`, and returns the index of the new subplot
+    pub fn add(&mut self, row: usize, col: usize, plot_builder: PlotBuilder2D) -> usize {
+        self.add_span(row, col, 1, 1, plot_builder)
+    }
+
+    /// `add_span` places a plot covering `row_span` rows and `col_span` columns, starting from the cell at `row`, `col`.
+    /// Returns the index of the new subplot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the subplot does not fit in the grid.
+    pub fn add_span(&mut self, row: usize, col: usize, row_span: usize, col_span: usize, plot_builder: PlotBuilder2D) -> usize {
+        assert!(
+            row_span > 0 && col_span > 0 && row + row_span <= self.rows && col + col_span <= self.cols,
+            "subplot does not fit in a {}x{} figure",
+            self.rows,
+            self.cols
+        );
+
+        self.subplots.push(Subplot {
+            plot_builder,
+            cell: Cell {
+                row,
+                col,
+                row_span,
+                col_span,
+            },
+            share_x: None,
+            share_y: None,
+        });
+        self.subplots.len() - 1
+    }
+
+    /// `share_x` makes the subplot at index `subplot` share its x axis with the subplot at index `with`.
+    /// Shared axes start out covering the data of every subplot sharing them, and zooming or panning
+    /// one of them moves them all. Subplots sharing an axis should use the same scale for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is not a subplot of this figure.
+    pub fn share_x(&mut self, subplot: usize, with: usize) {
+        assert!(with < self.subplots.len(), "no subplot {} to share an x axis with", with);
+        self.subplots[subplot].share_x = Some(with);
+    }
+
+    /// `share_y` makes the subplot at index `subplot` share its y axis with the subplot at index `with`.
+    /// See `share_x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is not a subplot of this figure.
+    pub fn share_y(&mut self, subplot: usize, with: usize) {
+        assert!(with < self.subplots.len(), "no subplot {} to share a y axis with", with);
+        self.subplots[subplot].share_y = Some(with);
+    }
+}
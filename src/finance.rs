@@ -0,0 +1,150 @@
+//! **finance** describes charts of market data, where each record is the open, high, low and close price over a period.
+//!
+//! Records are placed at their real times on a date axis, so days without trading, such as weekends and holidays,
+//! show up as gaps rather than being joined up. Every candle is as wide as the shortest step between two records allows.
+
+use datetime::Timestamp;
+use figure::Figure;
+use plotbuilder::PlotBuilder2D;
+
+/// `Ohlc` is the trading over one period
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ohlc {
+    /// **time** is when the period starts, in seconds since the Unix epoch
+    pub time: f64,
+
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+
+    /// **volume** optionally is the amount traded over the period
+    pub volume: Option<f64>,
+}
+
+impl Ohlc {
+    /// `new` creates a record without a volume
+    pub fn new<T: Timestamp>(time: T, open: f64, high: f64, low: f64, close: f64) -> Ohlc {
+        Ohlc {
+            time: time.epoch_seconds(),
+            open,
+            high,
+            low,
+            close,
+            volume: None,
+        }
+    }
+
+    /// `with_volume` returns the same record with the amount traded over the period
+    pub fn with_volume(self, volume: f64) -> Ohlc {
+        Ohlc {
+            volume: Some(volume),
+            ..self
+        }
+    }
+
+    /// `is_up` is whether the price closed at or above where it opened
+    pub fn is_up(&self) -> bool {
+        self.close >= self.open
+    }
+
+    /// `is_finite` is whether the time and all four prices are finite, so the record can be drawn
+    pub fn is_finite(&self) -> bool {
+        [self.time, self.open, self.high, self.low, self.close].iter().all(|v| v.is_finite())
+    }
+}
+
+/// `OhlcStyle` decides how each record is drawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OhlcStyle {
+    /// A box from the open to the close, with a wick from the low to the high
+    Candlestick,
+
+    /// A line from the low to the high, with a tick to the left at the open and a tick to the right at the close
+    Bars,
+
+    /// A bar from zero up to the volume. Records without a volume are left out.
+    Volume,
+}
+
+/// `Candles` describes a chart of market data, one mark per record
+#[derive(Clone, Debug)]
+pub struct Candles {
+    /// **records** contains the trading over each period. Records with prices that are not finite are left out.
+    pub records: Vec<Ohlc>,
+
+    /// **style** decides how the records are drawn
+    pub style: OhlcStyle,
+
+    /// **up_color** is the color of records that closed at or above where they opened
+    pub up_color: [f32; 4],
+
+    /// **down_color** is the color of records that closed below where they opened
+    pub down_color: [f32; 4],
+
+    /// **width** of each candle, as a fraction of the shortest step between two records
+    pub width: f64,
+}
+
+impl Candles {
+    /// `new` creates a candlestick chart of the records, green when the price went up and red when it went down
+    pub fn new(records: Vec<Ohlc>) -> Candles {
+        Candles {
+            records,
+            style: OhlcStyle::Candlestick,
+            up_color: [0.15, 0.6, 0.3, 1.0],
+            down_color: [0.8, 0.2, 0.2, 1.0],
+            width: 0.7,
+        }
+    }
+
+    /// `color` returns the color of a record
+    pub fn color(&self, record: &Ohlc) -> [f32; 4] {
+        if record.is_up() {
+            self.up_color
+        } else {
+            self.down_color
+        }
+    }
+}
+
+/// `with_volume` puts `price` in a figure above a smaller plot of the volume of `candles`, sharing the date axis,
+/// so zooming or panning along the dates moves both
+pub fn with_volume(price: PlotBuilder2D, candles: &Candles) -> Figure {
+    let mut volume = candles.clone();
+    volume.style = OhlcStyle::Volume;
+
+    let mut volume_pb = PlotBuilder2D::new();
+    volume_pb.add_candles(volume);
+    volume_pb.y_label = Some("volume".to_string());
+    volume_pb.min_y = Some(0.0);
+
+    let mut figure = Figure::new(4, 1);
+    let top = figure.add_span(0, 0, 3, 1, price);
+    let bottom = figure.add(3, 0, volume_pb);
+    figure.share_x(bottom, top);
+    figure
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn ohlc_test() {
+        let day = |d: u64| UNIX_EPOCH + Duration::from_secs(d * 86400);
+        let up = Ohlc::new(day(1), 10.0, 12.0, 9.0, 11.0).with_volume(500.0);
+        let down = Ohlc::new(day(4), 11.0, 11.5, 8.0, 9.0);
+        assert_eq!(up.time, 86400.0);
+        assert_eq!(up.volume, Some(500.0));
+        assert!(up.is_up() && !down.is_up());
+
+        let candles = Candles::new(vec![up, down]);
+        assert_eq!(candles.color(&down), candles.down_color);
+
+        let figure = with_volume(PlotBuilder2D::new(), &candles);
+        assert_eq!(figure.subplots.len(), 2);
+        assert_eq!(figure.subplots[1].share_x, Some(0));
+    }
+}
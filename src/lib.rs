@@ -43,8 +43,34 @@ pub mod draw_sdl;
 #[cfg(feature = "use-image")]
 extern crate image;
 
+#[cfg(feature = "use-chrono")]
+extern crate chrono;
+
+#[cfg(feature = "use-rayon")]
+extern crate rayon;
+
+#[cfg(feature = "use-ndarray")]
+extern crate ndarray;
+
+pub mod camera;
+pub mod colormap;
+pub mod column;
+pub mod contour;
+pub mod datetime;
+pub mod density;
+pub mod distribution;
 pub mod draw;
+pub mod figure;
+pub mod finance;
+#[cfg(feature = "use-sdl2")]
+mod font;
+pub mod lod;
 mod plot;
+mod plot3d;
+pub mod pie;
 pub mod plotter;
+pub mod polar;
+pub mod quiver;
 pub mod plotbuilder;
+pub mod scale;
 pub mod util;
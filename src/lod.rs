@@ -0,0 +1,267 @@
+//! **lod** thins lines out to the level of detail the screen can show, so series of millions of points stay responsive.
+//!
+//! Lines whose x values only go up are cut into pixel columns, and each column keeps just its first, lowest, highest
+//! and last points. That lights up the same pixels as every point would, with at most four points per column.
+//! Other lines drop points that are within a pixel of the last point kept.
+//! Either way the thinning is redone for every view, so zooming in brings the detail back.
+//!
+//! Lines are read through `column::Values`, so they can be thinned out straight from the caller's own buffers.
+
+use std::f64;
+use std::ops::Range;
+
+#[cfg(feature = "use-rayon")]
+use rayon::prelude::*;
+
+use column::Values;
+use draw::Range2d;
+
+/// The number of points thinned out at a time by each thread, with the `use-rayon` feature
+#[cfg(feature = "use-rayon")]
+pub const CHUNK: usize = 1 << 16;
+
+/// `Line` is the points of one line, as `decimate_all` takes them
+#[derive(Copy, Clone)]
+pub struct Line<'a> {
+    pub xs: &'a dyn Values,
+    pub ys: &'a dyn Values,
+
+    /// **sorted** must only be true if `is_sorted(xs)` is
+    pub sorted: bool,
+}
+
+/// `is_sorted` is whether the x values are all numbers and never go down, so the points in view can be found by bisection
+pub fn is_sorted<X: Values + ?Sized>(xs: &X) -> bool {
+    let mut last = f64::NEG_INFINITY;
+    (0..xs.len()).all(|i| {
+        let x = xs.at(i);
+        let sorted = x >= last;
+        last = x;
+        sorted
+    })
+}
+
+/// `decimate` returns the points of the line through `xs` and `ys` that matter in `view`, when a pixel is `pixel` in size.
+/// Points that are not finite come out as a `(NaN, NaN)` gap, so the line keeps its gaps.
+/// `sorted` must only be true if `is_sorted(xs)` is.
+///
+/// With the `use-rayon` feature, lines of more than `CHUNK` points are cut into chunks that are thinned out in parallel.
+/// The chunks are joined up again in order, so the line looks the same, though it may keep a few more points.
+pub fn decimate<X, Y>(xs: &X, ys: &Y, sorted: bool, view: Range2d, pixel: (f64, f64)) -> Vec<(f64, f64)>
+where
+    X: Values + ?Sized,
+    Y: Values + ?Sized,
+{
+    // columns of f64s are read straight from their slices, rather than converting each value
+    match (xs.as_f64(), ys.as_f64()) {
+        (Some(xs), Some(ys)) => decimate_values(xs, ys, sorted, view, pixel),
+        _ => decimate_values(xs, ys, sorted, view, pixel),
+    }
+}
+
+fn decimate_values<X, Y>(xs: &X, ys: &Y, sorted: bool, view: Range2d, pixel: (f64, f64)) -> Vec<(f64, f64)>
+where
+    X: Values + ?Sized,
+    Y: Values + ?Sized,
+{
+    let n = xs.len().min(ys.len());
+    let range = if sorted { visible(xs, n, view) } else { 0..n };
+    let thin = |r: Range<usize>| if sorted { min_max(xs, ys, r, view, pixel.0) } else { nearby(xs, ys, r, pixel) };
+
+    #[cfg(feature = "use-rayon")]
+    {
+        let chunks: Vec<Range<usize>> = range.clone().step_by(CHUNK).map(|start| start..(start + CHUNK).min(range.end)).collect();
+        let parts: Vec<Vec<(f64, f64)>> = chunks.into_par_iter().map(thin).collect();
+        parts.concat()
+    }
+    #[cfg(not(feature = "use-rayon"))]
+    thin(range)
+}
+
+/// `decimate_all` decimates each of `lines` for `view`, in parallel with the `use-rayon` feature
+pub fn decimate_all(lines: &[Line], view: Range2d, pixel: (f64, f64)) -> Vec<Vec<(f64, f64)>> {
+    let thin = |line: &Line| decimate(line.xs, line.ys, line.sorted, view, pixel);
+
+    #[cfg(feature = "use-rayon")]
+    {
+        lines.par_iter().map(thin).collect()
+    }
+    #[cfg(not(feature = "use-rayon"))]
+    lines.iter().map(thin).collect()
+}
+
+/// Adds a gap to the end of `out`, unless there is one already.
+/// A line may start with a gap, so that pieces of a line thinned out separately keep the gaps between them.
+fn gap(out: &mut Vec<(f64, f64)>) {
+    if !out.last().is_some_and(|p| p.0.is_nan()) {
+        out.push((f64::NAN, f64::NAN));
+    }
+}
+
+/// The range of the sorted `xs` in view, along with the points just either side of it so the line runs off the edges
+fn visible<X: Values + ?Sized>(xs: &X, n: usize, view: Range2d) -> Range<usize> {
+    let Range2d(w, _) = view;
+    let start = partition_point(xs, n, |x| x < w.min).saturating_sub(1);
+    let end = (partition_point(xs, n, |x| x <= w.max) + 1).min(n);
+    start..end
+}
+
+/// The index of the first of the first `n` sorted `xs` that is not `before`, by bisection
+fn partition_point<X: Values + ?Sized, F: Fn(f64) -> bool>(xs: &X, n: usize, before: F) -> usize {
+    let (mut low, mut high) = (0, n);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if before(xs.at(mid)) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Keeps the first, lowest, highest and last points of each pixel column, for the points in `range`
+fn min_max<X, Y>(xs: &X, ys: &Y, range: Range<usize>, view: Range2d, column: f64) -> Vec<(f64, f64)>
+where
+    X: Values + ?Sized,
+    Y: Values + ?Sized,
+{
+    let Range2d(w, _) = view;
+    if column.is_nan() || column <= 0.0 {
+        return range.map(|i| (xs.at(i), ys.at(i))).collect();
+    }
+
+    let mut out = Vec::new();
+
+    // the column being filled, and the indices of its first, lowest, highest and last points
+    let mut current: Option<(f64, [usize; 4])> = None;
+    let flush = |out: &mut Vec<(f64, f64)>, bucket: [usize; 4]| {
+        let mut picked = bucket;
+        picked.sort();
+        for (k, &i) in picked.iter().enumerate() {
+            if k == 0 || picked[k - 1] != i {
+                out.push((xs.at(i), ys.at(i)));
+            }
+        }
+    };
+
+    for i in range {
+        let (x, y) = (xs.at(i), ys.at(i));
+        if !(x.is_finite() && y.is_finite()) {
+            if let Some((_, bucket)) = current.take() {
+                flush(&mut out, bucket);
+            }
+            gap(&mut out);
+            continue;
+        }
+
+        let col = ((x - w.min) / column).floor();
+        match current {
+            Some((c, ref mut bucket)) if c == col => {
+                if y < ys.at(bucket[1]) {
+                    bucket[1] = i;
+                }
+                if y > ys.at(bucket[2]) {
+                    bucket[2] = i;
+                }
+                bucket[3] = i;
+            }
+            _ => {
+                if let Some((_, bucket)) = current {
+                    flush(&mut out, bucket);
+                }
+                current = Some((col, [i; 4]));
+            }
+        }
+    }
+    if let Some((_, bucket)) = current {
+        flush(&mut out, bucket);
+    }
+    out
+}
+
+/// Keeps the points in `range` that are more than a pixel away from the last point kept, and the last point of every stretch between gaps
+fn nearby<X, Y>(xs: &X, ys: &Y, range: Range<usize>, pixel: (f64, f64)) -> Vec<(f64, f64)>
+where
+    X: Values + ?Sized,
+    Y: Values + ?Sized,
+{
+    let mut out: Vec<(f64, f64)> = Vec::new();
+
+    // the last point passed over, which ends its stretch if the next point is a gap
+    let mut skipped = None;
+    for i in range {
+        let (x, y) = (xs.at(i), ys.at(i));
+        if !(x.is_finite() && y.is_finite()) {
+            if let Some(p) = skipped.take() {
+                out.push(p);
+            }
+            gap(&mut out);
+            continue;
+        }
+
+        match out.last() {
+            Some(&(lx, ly)) if !lx.is_nan() && (x - lx).abs() < pixel.0 && (y - ly).abs() < pixel.1 => skipped = Some((x, y)),
+            _ => {
+                out.push((x, y));
+                skipped = None;
+            }
+        }
+    }
+    if let Some(p) = skipped {
+        out.push(p);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use draw::Range;
+
+    #[test]
+    fn decimate_test() {
+        let view = Range2d(Range { min: 0.0, max: 10.0 }, Range { min: -1.0, max: 1.0 });
+
+        // a thousand points zigzagging across ten pixel columns keep three points per column,
+        // because the first point of each column is also its highest
+        let xs: Vec<f64> = (0..1000).map(|i| i as f64 / 100.0).collect();
+        let ys: Vec<f64> = (0..1000).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }).collect();
+        assert!(is_sorted(&xs));
+        let points = decimate(&xs, &ys, true, view, (1.0, 0.1));
+        assert_eq!(points.len(), 30);
+        assert_eq!(points[0], (0.0, 1.0));
+        assert_eq!(points[29], (9.99, -1.0));
+
+        // only the points just outside the view are kept beyond it, and gaps are kept once
+        let xs = [-3.0, -2.0, 1.0, 2.0, 2.5, 12.0, 13.0];
+        let ys = [0.0, 0.0, f64::NAN, f64::NAN, 0.5, 0.0, 0.0];
+        let points = decimate(&xs[..], &ys[..], is_sorted(&xs[..]), view, (1.0, 0.1));
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0], (-2.0, 0.0));
+        assert!(points[1].0.is_nan());
+        assert_eq!(points[3], (12.0, 0.0));
+
+        // a circle drawn with far more points than pixels
+        let xs: Vec<f64> = (0..10000).map(|i| (i as f64 / 10000.0 * 6.3).cos()).collect();
+        let ys: Vec<f64> = (0..10000).map(|i| (i as f64 / 10000.0 * 6.3).sin()).collect();
+        assert!(!is_sorted(&xs));
+        let points = decimate(&xs, &ys, false, view, (0.01, 0.01));
+        assert!(points.len() < 1000);
+        assert_eq!(points[points.len() - 1], (xs[9999], ys[9999]));
+    }
+
+    #[cfg(feature = "use-rayon")]
+    #[test]
+    fn chunk_test() {
+        // when the chunks end on pixel columns, thinning them out in parallel keeps exactly the same points
+        let n = 4 * CHUNK;
+        let xs: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        let ys: Vec<f64> = (0..n).map(|i| if i % 1000 == 999 { f64::NAN } else { (i as f64).sin() }).collect();
+        let view = Range2d(Range { min: 0.0, max: n as f64 }, Range { min: -1.0, max: 1.0 });
+        let points = decimate(&xs, &ys, true, view, (256.0, 0.01));
+        let serial = min_max(&xs[..], &ys[..], 0..n, view, 256.0);
+        assert_eq!(points.len(), serial.len());
+        assert!(points.iter().zip(&serial).all(|(a, b)| a == b || (a.0.is_nan() && b.0.is_nan())));
+    }
+}
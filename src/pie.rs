@@ -0,0 +1,130 @@
+//! **pie** describes pie charts and donut charts, where each slice shows a value's share of the total.
+//!
+//! Pie charts are laid out on a unit disk, like polar axes, and take up the whole of their axes.
+//! Several pies on the same axes are drawn on top of each other, so donuts with different radii nest as rings.
+
+use std::f64;
+
+/// The colors the slices go through, in order, when no colors are given
+pub const SLICE_COLORS: [[f32; 4]; 8] = [
+    [0.12, 0.47, 0.71, 1.0],
+    [1.0, 0.5, 0.05, 1.0],
+    [0.17, 0.63, 0.17, 1.0],
+    [0.84, 0.15, 0.16, 1.0],
+    [0.58, 0.4, 0.74, 1.0],
+    [0.55, 0.34, 0.29, 1.0],
+    [0.89, 0.47, 0.76, 1.0],
+    [0.5, 0.5, 0.5, 1.0],
+];
+
+/// `Pie` describes a pie chart, or a donut chart if `inner_radius` is more than zero
+#[derive(Clone, Debug)]
+pub struct Pie {
+    /// **slices** contains the name and value of each slice. Values that are negative or not finite count as zero.
+    pub slices: Vec<(String, f64)>,
+
+    /// **colors** fills the slices, starting over from the first color if there are more slices than colors
+    pub colors: Vec<[f32; 4]>,
+
+    /// **start_angle** is where the first slice starts, in degrees counterclockwise from the right.
+    /// `90.0` starts at the top, like a clock.
+    pub start_angle: f64,
+
+    /// **clockwise** lays the slices out clockwise instead of counterclockwise
+    pub clockwise: bool,
+
+    /// **radius** is the outer radius, where 1 is the edge of the unit disk
+    pub radius: f64,
+
+    /// **inner_radius** cuts a hole of this radius out of the middle, for a donut chart
+    pub inner_radius: f64,
+
+    /// **explode** pulls slices out from the middle, by a fraction of the radius.
+    /// It has one entry per slice, and missing entries count as zero.
+    pub explode: Vec<f64>,
+
+    /// **labels** writes the name of each slice next to it
+    pub labels: bool,
+
+    /// **percentages** writes each slice's share of the total on it
+    pub percentages: bool,
+}
+
+impl Pie {
+    /// `new` creates a pie chart of the given slices, starting at the top and going clockwise, with labels and percentages
+    pub fn new<S: AsRef<str>>(slices: Vec<(S, f64)>) -> Pie {
+        Pie {
+            slices: slices.into_iter().map(|(name, value)| (name.as_ref().to_string(), value)).collect(),
+            colors: SLICE_COLORS.to_vec(),
+            start_angle: 90.0,
+            clockwise: true,
+            radius: 1.0,
+            inner_radius: 0.0,
+            explode: vec![],
+            labels: true,
+            percentages: true,
+        }
+    }
+
+    /// `fractions` returns each slice's share of the total, or all zeros if the total is zero
+    pub fn fractions(&self) -> Vec<f64> {
+        let values: Vec<f64> = self.slices.iter().map(|s| if s.1.is_finite() && s.1 > 0.0 { s.1 } else { 0.0 }).collect();
+        let total: f64 = values.iter().sum();
+        values.iter().map(|v| if total > 0.0 { v / total } else { 0.0 }).collect()
+    }
+
+    /// `angles` returns where each slice starts and ends on screen, in radians counterclockwise from the right
+    pub fn angles(&self) -> Vec<(f64, f64)> {
+        let sense = if self.clockwise { -1.0 } else { 1.0 };
+        let mut angle = self.start_angle.to_radians();
+        self.fractions()
+            .into_iter()
+            .map(|f| {
+                let start = angle;
+                angle += sense * f * 2.0 * f64::consts::PI;
+                (start, angle)
+            })
+            .collect()
+    }
+
+    /// `color` returns the color of slice `i`
+    pub fn color(&self, i: usize) -> [f32; 4] {
+        if self.colors.is_empty() {
+            SLICE_COLORS[i % SLICE_COLORS.len()]
+        } else {
+            self.colors[i % self.colors.len()]
+        }
+    }
+
+    /// `offset` returns how far slice `i` is pulled out from the middle
+    pub fn offset(&self, i: usize) -> f64 {
+        self.explode.get(i).cloned().unwrap_or(0.0).max(0.0) * self.radius
+    }
+}
+
+/// `format_percentage` writes a share of the total as a percentage, e.g. `12.5%`
+pub fn format_percentage(fraction: f64) -> String {
+    format!("{:.1}%", fraction * 100.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pie_test() {
+        let mut pie = Pie::new(vec![("a", 1.0), ("b", 3.0), ("c", -2.0), ("d", f64::NAN)]);
+        assert_eq!(pie.fractions(), vec![0.25, 0.75, 0.0, 0.0]);
+
+        // from the top, clockwise
+        let angles = pie.angles();
+        assert!((angles[0].0 - f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert!(angles[0].1.abs() < 1e-12);
+        assert!((angles[1].1 + 3.0 * f64::consts::FRAC_PI_2).abs() < 1e-12);
+
+        pie.explode = vec![0.0, 0.1];
+        assert_eq!((pie.offset(1), pie.offset(3)), (0.1, 0.0));
+        assert_eq!(pie.color(9), SLICE_COLORS[1]);
+        assert_eq!(format_percentage(0.125), "12.5%");
+    }
+}
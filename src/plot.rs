@@ -2,44 +2,191 @@
 //!
 //! Users of **dataplotlib** should not need to access **plot**.
 
-use std::time::Duration;
-use std::{mem, thread, f64};
+use std::{mem, f64};
 
 use plotbuilder::*;
+use figure::{Cell, Figure};
+use contour::{self, Contour};
+use density::{Bins, BinShape, Density};
+use colormap::Colormap;
+use column::{Column, Values};
+use distribution::{self, BoxPlot, BoxStats, Violin};
+use finance::{Candles, Ohlc, OhlcStyle};
+use lod;
+use pie::{self, Pie};
+use polar::{self, Polar};
+use quiver::Quiver;
+use scale::{self, AxisKind, Scale, Tick};
+use datetime;
+use plotter::{Link, Redraw};
+use plot3d::draw_plot3d;
 
-use draw::{Drawable, Event, Range, Range2d};
+use draw::{self, Drawable, Event, HAlign, MouseButton, Range, Range2d, VAlign};
 
 pub struct Plot {}
 
-fn get_max(user_max: Option<f64>, values: &Vec<f64>) -> f64 {
+/// Length of the major tick marks, in pixels
+pub const TICK_LENGTH: f64 = 6.0;
+
+/// Space around the plot area and between labels and ticks, in pixels
+pub const PADDING: f64 = 12.0;
+
+/// A color and a list of points in scaled coordinates
+type ColoredPoints = ([f32; 4], Vec<(f64, f64)>);
+
+/// A box plot of one group, in scaled coordinates
+struct BoxShape {
+    color: [f32; 4],
+    x: f64,
+    half_width: f64,
+    stats: BoxStats,
+    notch: bool,
+    outliers: bool,
+}
+
+/// A violin plot of one group, in scaled coordinates
+struct ViolinShape {
+    color: [f32; 4],
+    x: f64,
+    outline: Vec<(f64, f64)>,
+    quartiles: Option<BoxStats>,
+}
+
+/// A stem plot, with the baseline and points in scaled coordinates
+struct StemShape {
+    color: [f32; 4],
+    baseline: f64,
+    points: Vec<(f64, f64)>,
+}
+
+/// Market data, with the records in scaled coordinates
+struct CandleShape {
+    candles: Candles,
+    half_width: f64,
+}
+
+/// One arrow of a quiver plot, starting from a point in scaled coordinates
+struct Arrow {
+    start: (f64, f64),
+    vector: (f64, f64),
+    color: [f32; 4],
+}
+
+/// The arrows of a quiver plot
+struct QuiverShape {
+    arrows: Vec<Arrow>,
+
+    /// The length of an arrow of magnitude 1, as a fraction of the smaller side of the plot area
+    scale: f64,
+
+    /// The magnitude and color of the reference arrow
+    key: Option<(f64, [f32; 4])>,
+}
+
+/// `Series` is everything drawn against one y-axis.
+/// All coordinates have already been transformed by the axis scales.
+struct Series {
+    xs: Vec<Column>,
+    ys: Vec<Column>,
+    colors: Vec<[f32; 4]>,
+
+    /// Whether the x values of each line only go up, which lets them be thinned out faster
+    sorted: Vec<bool>,
+    contours: Vec<Contour>,
+    bars: Vec<ColoredPoints>,
+    boxes: Vec<BoxShape>,
+    violins: Vec<ViolinShape>,
+    quivers: Vec<QuiverShape>,
+    candles: Vec<CandleShape>,
+    stems: Vec<StemShape>,
+
+    /// Densities are binned for every view, so they keep all of their points
+    densities: Vec<Density>,
+}
+
+/// `SecondaryAxis` is the y-axis on the right, with its own range, ticks and label
+struct SecondaryAxis {
+    series: Series,
+    scale: Scale,
+    label: Option<String>,
+    axis: bool,
+
+    /// The part of the axis currently in view
+    view: Range,
+
+    /// The range to go back to when the plot is reset
+    home: Range,
+}
+
+/// `PolarAxes` is what polar axes draw, already placed on the unit disk
+struct PolarAxes {
+    polar: Polar,
+
+    /// The radius at the edge of the disk
+    max_r: f64,
+
+    lines: Vec<ColoredPoints>,
+
+    /// The wedges of rose diagrams
+    wedges: Vec<ColoredPoints>,
+}
+
+/// `Axes` is one set of axes and the series drawn on it
+struct Axes {
+    series: Series,
+    y2: Option<SecondaryAxis>,
+    polar: Option<PolarAxes>,
+
+    /// Pie charts take the place of everything else
+    pies: Vec<Pie>,
+    x_scale: Scale,
+    y_scale: Scale,
+    x_kind: AxisKind,
+    y_kind: AxisKind,
+    x_axis: bool,
+    y_axis: bool,
+    x_gridlines: bool,
+    y_gridlines: bool,
+    crosshair: bool,
+    title: Option<String>,
+    x_label: Option<String>,
+    y_label: Option<String>,
+
+    /// Where in the figure the axes go
+    cell: Cell,
+
+    /// The part of the plot currently in view
+    view: Range2d,
+
+    /// The view to go back to when the plot is reset
+    home: Range2d,
+
+    /// Axes with the same group share their x axis
+    x_group: usize,
+
+    /// Axes with the same group share their y axis
+    y_group: usize,
+}
+
+/// The largest finite value, or the user's choice if they made one
+fn get_max(user_max: Option<f64>, values: &[f64]) -> f64 {
     if let Some(max) = user_max {
         max
     } else {
-        let mut max = *values.first().unwrap();
-        for val in values {
-            if *val > max {
-                max = *val;
-            }
-        }
-        max
+        values.iter().cloned().filter(|v| v.is_finite()).fold(f64::NAN, f64::max)
     }
 }
 
-fn get_min(user_min: Option<f64>, values: &Vec<f64>) -> f64 {
+/// The smallest finite value, or the user's choice if they made one
+fn get_min(user_min: Option<f64>, values: &[f64]) -> f64 {
     if let Some(min) = user_min {
         min
     } else {
-        let mut min = *values.first().unwrap();
-        for val in values {
-            if *val < min {
-                min = *val;
-            }
-        }
-        min
+        values.iter().cloned().filter(|v| v.is_finite()).fold(f64::NAN, f64::min)
     }
 }
 
-fn f32_4_to_color(col: [f32; 4]) -> [u8; 4] {
+pub fn f32_4_to_color(col: [f32; 4]) -> [u8; 4] {
     [
         (col[0] * 255f32) as u8,
         (col[1] * 255f32) as u8,
@@ -48,10 +195,7 @@ fn f32_4_to_color(col: [f32; 4]) -> [u8; 4] {
     ]
 }
 
-fn draw_borders(bordercol: [u8; 4], bgcol: [u8; 4], space: (f64, f64), m: (f64, f64), renderer: &mut Drawable) {
-    renderer.set_color(bordercol);
-    renderer.clear();
-
+fn draw_borders(bgcol: [u8; 4], space: (f64, f64), m: (f64, f64), renderer: &mut dyn Drawable) {
     renderer.set_color(bgcol);
     renderer.rectangle(space, m);
 
@@ -59,18 +203,20 @@ fn draw_borders(bordercol: [u8; 4], bgcol: [u8; 4], space: (f64, f64), m: (f64,
     renderer.unfilled_rectangle(space, m);
 }
 
-fn set_xy(xy: &Vec<(f64, f64)>, x_vector: &mut Vec<Vec<f64>>, y_vector: &mut Vec<Vec<f64>>) {
-    x_vector.push(Vec::new());
-    y_vector.push(Vec::new());
-
-    let last_index = x_vector.len() - 1;
-
-    for &(x, y) in xy {
-        x_vector[last_index].push(x);
-        y_vector[last_index].push(y);
+/// Transforms a column by `scale`, which leaves it as it is on a linear scale so that it isn't copied
+fn scale_column(values: Column, scale: Scale) -> Column {
+    match scale {
+        Scale::Linear => values,
+        _ => (0..values.len()).map(|i| scale.forward(values.at(i))).collect::<Vec<f64>>().into(),
     }
 }
 
+/// The lowest and highest finite values of a column, or NaN if there are none
+fn column_bounds(values: &Column) -> [f64; 2] {
+    let finite = (0..values.len()).map(|i| values.at(i)).filter(|v| v.is_finite());
+    finite.fold([f64::NAN, f64::NAN], |b, v| [b[0].min(v), b[1].max(v)])
+}
+
 fn clip_line(mut a: (f64, f64), mut b: (f64, f64), view: Range2d) -> Option<((f64, f64), (f64, f64))> {
     //trivial accept
     if view.contains(a) && view.contains(b) {
@@ -123,168 +269,1943 @@ fn clip_line(mut a: (f64, f64), mut b: (f64, f64), view: Range2d) -> Option<((f6
     return clip_line(a, b, view);
 }
 
-fn draw_plots(renderer: &mut Drawable, xs: &Vec<Vec<f64>>, ys: &Vec<Vec<f64>>, colors: &Vec<[f32; 4]>, plot_bounds: [f64; 4]) {
-    let bordercol = f32_4_to_color([0.95, 0.95, 0.95, 1.0]);
-    let bgcol = f32_4_to_color([1.0, 1.0, 1.0, 1.0]);
-    let margin = 0.05;
+/// Clips the line through `points` to `view`, splitting it into the runs that stay inside.
+/// Points that are not finite leave a gap.
+fn clip_polyline(points: &[(f64, f64)], view: Range2d) -> Vec<Vec<(f64, f64)>> {
+    clip_runs(points, |a, b| clip_line(a, b, view))
+}
+
+/// Clips each piece of the line through `points` with `clip`, joining the clipped pieces that still meet into runs.
+/// Points that are not finite leave a gap.
+fn clip_runs<C>(points: &[(f64, f64)], clip: C) -> Vec<Vec<(f64, f64)>>
+    where C: Fn((f64, f64), (f64, f64)) -> Option<((f64, f64), (f64, f64))>
+{
+    let mut runs: Vec<Vec<(f64, f64)>> = Vec::new();
+
+    // whether the last run ended at the last point, so the next piece carries on from it
+    let mut joined = false;
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if !(a.0.is_finite() && a.1.is_finite() && b.0.is_finite() && b.1.is_finite()) {
+            joined = false;
+            continue;
+        }
+
+        match clip(a, b) {
+            Some((ca, cb)) => {
+                match runs.last_mut() {
+                    Some(run) if joined && ca == a => run.push(cb),
+                    _ => runs.push(vec![ca, cb]),
+                }
+                // a piece clipped at its end leaves the view, so the next piece starts a new run
+                joined = cb == b;
+            }
+            None => joined = false,
+        }
+    }
+    runs
+}
 
-    let w = Range {
-        min: plot_bounds[2],
-        max: plot_bounds[0],
+/// Clips a polygon to one edge of the view, keeping the points where `inside` holds.
+/// `cross` finds where a segment meets the edge.
+fn clip_polygon_edge<I, C>(points: &[(f64, f64)], inside: I, cross: C) -> Vec<(f64, f64)>
+    where I: Fn((f64, f64)) -> bool,
+          C: Fn((f64, f64), (f64, f64)) -> (f64, f64)
+{
+    let mut output = Vec::with_capacity(points.len() + 2);
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        if inside(a) {
+            output.push(a);
+        }
+        if inside(a) != inside(b) {
+            output.push(cross(a, b));
+        }
+    }
+    output
+}
+
+/// Clips a polygon to the view with Sutherland-Hodgman, one view edge at a time
+fn clip_polygon(points: &[(f64, f64)], view: Range2d) -> Vec<(f64, f64)> {
+    let Range2d(w, h) = view;
+    let at_x = |x: f64| move |a: (f64, f64), b: (f64, f64)| (x, a.1 + (b.1 - a.1) * (x - a.0) / (b.0 - a.0));
+    let at_y = |y: f64| move |a: (f64, f64), b: (f64, f64)| (a.0 + (b.0 - a.0) * (y - a.1) / (b.1 - a.1), y);
+
+    let points = clip_polygon_edge(points, |p| p.0 >= w.min, at_x(w.min));
+    let points = clip_polygon_edge(&points, |p| p.0 <= w.max, at_x(w.max));
+    let points = clip_polygon_edge(&points, |p| p.1 >= h.min, at_y(h.min));
+    clip_polygon_edge(&points, |p| p.1 <= h.max, at_y(h.max))
+}
+
+/// Clips a polygon to the inside of a convex polygon whose points go counterclockwise, such as `polar::circle`
+fn clip_polygon_convex(points: &[(f64, f64)], hull: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut points = points.to_vec();
+    for i in 0..hull.len() {
+        let (c, d) = (hull[i], hull[(i + 1) % hull.len()]);
+        let side = |p: (f64, f64)| (d.0 - c.0) * (p.1 - c.1) - (d.1 - c.1) * (p.0 - c.0);
+        let cross = |a: (f64, f64), b: (f64, f64)| {
+            let t = side(a) / (side(a) - side(b));
+            (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+        };
+        points = clip_polygon_edge(&points, |p| side(p) >= 0.0, cross);
+    }
+    points
+}
+
+fn draw_contour(renderer: &mut dyn Drawable, c: &Contour, view: Range2d) {
+    let (z_min, z_max) = c.z_range();
+
+    if c.filled {
+        let bounds = c.band_bounds();
+        for band in bounds.windows(2) {
+            let color = c.colormap.color_in((band[0] + band[1]) / 2.0, z_min, z_max);
+            renderer.set_color(f32_4_to_color(color));
+            for polygon in contour::contour_bands(&c.xs, &c.ys, &c.zs, band[0], band[1]) {
+                let clipped = clip_polygon(&polygon, view);
+                if clipped.len() >= 3 {
+                    renderer.polygon(&clipped);
+                }
+            }
+        }
+        return;
+    }
+
+    let levels = c.level_values();
+    for &level in &levels {
+        let color = c.color.unwrap_or_else(|| c.colormap.color_in(level, z_min, z_max));
+        renderer.set_color(f32_4_to_color(color));
+
+        for line in contour::contour_lines(&c.xs, &c.ys, &c.zs, level) {
+            for run in clip_polyline(&line, view) {
+                renderer.polyline(&run, 2);
+            }
+
+            // only label lines long enough that the label doesn't hide them
+            if c.labels && line.len() >= 8 {
+                let mid = line[line.len() / 2];
+                if view.contains(mid) {
+                    renderer.text(mid, &contour::format_level(level, &levels), HAlign::Center, VAlign::Center);
+                }
+            }
+        }
+    }
+}
+
+/// Ticks within the visible part `view` of an axis, which is in scaled coordinates.
+/// `spacing` is roughly how many pixels apart numeric ticks should be.
+fn visible_ticks(scale: Scale, kind: &AxisKind, view: Range, pixels: f64, spacing: f64) -> Vec<(f64, Tick)> {
+    let (a, b) = (scale.inverse(view.min), scale.inverse(view.max));
+    let (min, max) = (a.min(b), a.max(b));
+    let ticks = match *kind {
+        AxisKind::Numeric => scale.ticks(min, max, (pixels / spacing) as usize),
+        // dates have longer labels, so they need more room
+        AxisKind::DateTime => datetime::date_ticks(min, max, (pixels / (spacing * 1.5)) as usize),
+        AxisKind::Categorical(ref names) => scale::category_ticks(names, min, max, (pixels / spacing) as usize),
     };
+    ticks
+        .into_iter()
+        .map(|t| (scale.forward(t.value), t))
+        .filter(|&(pos, _)| view.contains(pos))
+        .collect()
+}
+
+/// Draws bars of 80% of the smallest gap between neighbouring x values, from zero (or the bottom of the view) to y
+fn draw_bars(renderer: &mut dyn Drawable, color: [f32; 4], xy: &[(f64, f64)], baseline: f64, view: Range2d) {
+    let Range2d(w, h) = view;
+
+    let mut xs: Vec<f64> = xy.iter().map(|p| p.0).filter(|x| x.is_finite()).collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let gap = xs.windows(2).map(|p| p[1] - p[0]).filter(|&d| d > 0.0).fold(f64::INFINITY, f64::min);
+    let half_width = if gap.is_finite() { gap * 0.4 } else { 0.4 };
+
+    let baseline = if baseline.is_finite() { baseline } else { h.min };
+    let clamp = |v: f64, r: Range| v.max(r.min).min(r.max);
+
+    renderer.set_color(f32_4_to_color(color));
+    for &(x, y) in xy {
+        if !x.is_finite() || !y.is_finite() || x + half_width < w.min || x - half_width > w.max {
+            continue;
+        }
+        let a = (clamp(x - half_width, w), clamp(baseline, h));
+        let b = (clamp(x + half_width, w), clamp(y, h));
+        if a.1 != b.1 {
+            renderer.rectangle(a, b);
+        }
+    }
+}
+
+/// Worldspace size of a pixel in the current view of `renderer`
+fn pixel_size(renderer: &dyn Drawable) -> (f64, f64) {
+    let Range2d(w, h) = renderer.get_view();
+    let (win_w, win_h) = renderer.get_size();
+    (w.size() / win_w, h.size() / win_h)
+}
+
+/// Draws the edges of a closed polygon, clipped to `view`
+fn draw_outline(renderer: &mut dyn Drawable, points: &[(f64, f64)], view: Range2d) {
+    if points.iter().all(|&p| view.contains(p)) {
+        renderer.unfilled_polygon(points);
+        return;
+    }
+
+    let mut closed = points.to_vec();
+    closed.extend(points.first());
+    for run in clip_polyline(&closed, view) {
+        renderer.polyline(&run, 1);
+    }
+}
+
+/// Fills a polygon with `color` and outlines it with `edge`, clipped to `view`
+fn draw_shape(renderer: &mut dyn Drawable, points: &[(f64, f64)], color: [f32; 4], edge: [u8; 4], view: Range2d) {
+    let clipped = clip_polygon(points, view);
+    if clipped.len() >= 3 {
+        renderer.set_color(f32_4_to_color(color));
+        renderer.polygon(&clipped);
+    }
+    renderer.set_color(edge);
+    draw_outline(renderer, points, view);
+}
+
+fn draw_box(renderer: &mut dyn Drawable, b: &BoxShape, view: Range2d) {
+    let edgecol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+    let (x, hw, s) = (b.x, b.half_width, &b.stats);
 
-    let h = Range {
-        min: plot_bounds[3],
-        max: plot_bounds[1],
+    let mut median_hw = hw;
+    let outline = if b.notch {
+        // the notch stays inside the box, even when it is very uncertain
+        let (low, high) = (s.notch_low.max(s.q1), s.notch_high.min(s.q3));
+        median_hw = hw / 2.0;
+        vec![
+            (x - hw, s.q1),
+            (x + hw, s.q1),
+            (x + hw, low),
+            (x + median_hw, s.median),
+            (x + hw, high),
+            (x + hw, s.q3),
+            (x - hw, s.q3),
+            (x - hw, high),
+            (x - median_hw, s.median),
+            (x - hw, low),
+        ]
+    } else {
+        vec![(x - hw, s.q1), (x + hw, s.q1), (x + hw, s.q3), (x - hw, s.q3)]
     };
+    draw_shape(renderer, &outline, b.color, edgecol, view);
 
-    renderer.set_view(Range2d(w, h));
+    renderer.set_color(edgecol);
+    let lines = [
+        ((x - median_hw, s.median), (x + median_hw, s.median)),
+        ((x, s.q1), (x, s.whisker_low)),
+        ((x, s.q3), (x, s.whisker_high)),
+        ((x - hw / 2.0, s.whisker_low), (x + hw / 2.0, s.whisker_low)),
+        ((x - hw / 2.0, s.whisker_high), (x + hw / 2.0, s.whisker_high)),
+    ];
+    for (i, &(a, b)) in lines.iter().enumerate() {
+        if let Some((a, b)) = clip_line(a, b, view) {
+            // the median stands out from the rest
+            renderer.thick_line(a, b, if i == 0 { 2 } else { 1 });
+        }
+    }
 
-    let update_frame = |renderer: &mut Drawable| {
-        let Range2d(w, h) = renderer.get_view();
+    if b.outliers {
+        let (px, py) = pixel_size(renderer);
+        for &o in &s.outliers {
+            if view.contains((x, o)) {
+                renderer.unfilled_rectangle((x - 3.0 * px, o - 3.0 * py), (x + 3.0 * px, o + 3.0 * py));
+            }
+        }
+    }
+}
 
-        // calculate margins around plot
-        let w_marg = w.size() * margin;
-        let h_marg = h.size() * margin;
+fn draw_violin(renderer: &mut dyn Drawable, v: &ViolinShape, view: Range2d) {
+    let edgecol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+    draw_shape(renderer, &v.outline, v.color, edgecol, view);
 
-        // set up a "fake" plot view that has extra margins
-        let w_fake = Range {
-            min: w.min - w_marg,
-            max: w.max + w_marg,
-        };
-        let h_fake = Range {
-            min: h.min - h_marg,
-            max: h.max + h_marg,
-        };
-        renderer.set_view(Range2d(w_fake, h_fake));
+    if let Some(ref s) = v.quartiles {
+        renderer.set_color(edgecol);
+        if let Some((a, b)) = clip_line((v.x, s.q1), (v.x, s.q3), view) {
+            renderer.thick_line(a, b, 4);
+        }
+        if view.contains((v.x, s.median)) {
+            let (px, py) = pixel_size(renderer);
+            renderer.set_color([255, 255, 255, 255]);
+            renderer.rectangle((v.x - 2.0 * px, s.median - 2.0 * py), (v.x + 2.0 * px, s.median + 2.0 * py));
+        }
+    }
+}
 
-        // the borders are just the edges of the real view
-        let border_min = (w.min, h.min);
-        let border_max = (w.max, h.max);
+/// Draws a stem from the baseline, or the bottom of the view if the scale can't show it, to each point,
+/// with a round marker at the point
+fn draw_stems(renderer: &mut dyn Drawable, stem: &StemShape, view: Range2d) {
+    let (px, py) = pixel_size(renderer);
+    let baseline = if stem.baseline.is_finite() { stem.baseline } else { view.1.min };
+    let marker: Vec<(f64, f64)> = draw::arc_points((0.0, 0.0), 3.5, 0.0, 2.0 * f64::consts::PI);
 
-        draw_borders(bordercol, bgcol, border_min, border_max, renderer);
+    renderer.set_color(f32_4_to_color(stem.color));
+    let first = stem.points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let last = stem.points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    if let Some((a, b)) = clip_line((first, baseline), (last, baseline), view) {
+        renderer.line(a, b);
+    }
 
-        for i in 0..colors.len() {
-            let color = colors[i];
-            let color_rgba = f32_4_to_color(color);
-            renderer.set_color(color_rgba);
+    for &(x, y) in &stem.points {
+        if let Some((a, b)) = clip_line((x, baseline), (x, y), view) {
+            renderer.line(a, b);
+        }
+        if view.contains((x, y)) {
+            let head: Vec<(f64, f64)> = marker.iter().map(|m| (x + m.0 * px, y + m.1 * py)).collect();
+            renderer.polygon(&clip_polygon(&head, view));
+        }
+    }
+}
 
-            let yt = &ys[i];
-            let xt = &xs[i];
+/// Draws market data as candlesticks, OHLC bars or volume bars, clipped to `view`. Volume bars start from zero on `y_scale`.
+fn draw_candles(renderer: &mut dyn Drawable, c: &CandleShape, y_scale: Scale, view: Range2d) {
+    let Range2d(w, h) = view;
+    let hw = c.half_width;
+    let clamp = |v: f64, r: Range| v.max(r.min).min(r.max);
+    let line = |renderer: &mut dyn Drawable, a: (f64, f64), b: (f64, f64)| {
+        if let Some((a, b)) = clip_line(a, b, view) {
+            renderer.line(a, b);
+        }
+    };
 
-            // The number of points
-            let len = xs[i].len();
-            for j in 0..len - 1 {
-                let a = (xt[j + 0], yt[j + 0]);
-                let b = (xt[j + 1], yt[j + 1]);
+    for r in &c.candles.records {
+        if r.time + hw < w.min || r.time - hw > w.max {
+            continue;
+        }
+        renderer.set_color(f32_4_to_color(c.candles.color(r)));
+        let x = r.time;
 
-                if let Some(((xa, ya), (xb, yb))) = clip_line(a, b, Range2d(w, h)) {
-                    renderer.thick_line((xa, ya), (xb, yb), 2);
+        match c.candles.style {
+            OhlcStyle::Candlestick => {
+                line(renderer, (x, r.low), (x, r.high));
+                let (bottom, top) = (clamp(r.open.min(r.close), h), clamp(r.open.max(r.close), h));
+                if top > bottom {
+                    renderer.rectangle((clamp(x - hw, w), bottom), (clamp(x + hw, w), top));
+                } else {
+                    // the price didn't move, so the body is just a line
+                    line(renderer, (x - hw, r.open), (x + hw, r.open));
+                }
+            }
+            OhlcStyle::Bars => {
+                line(renderer, (x, r.low), (x, r.high));
+                line(renderer, (x - hw, r.open), (x, r.open));
+                line(renderer, (x, r.close), (x + hw, r.close));
+            }
+            OhlcStyle::Volume => {
+                let baseline = y_scale.forward(0.0);
+                let baseline = if baseline.is_finite() { baseline } else { h.min };
+                let (a, b) = ((clamp(x - hw, w), clamp(baseline, h)), (clamp(x + hw, w), clamp(r.volume.unwrap_or(baseline), h)));
+                if a.1 != b.1 {
+                    renderer.rectangle(a, b);
                 }
             }
         }
+    }
+}
 
-        renderer.present();
+/// Length of an arrow head, in pixels
+const ARROW_HEAD: f64 = 8.0;
 
-        // reset the view to the real view
-        renderer.set_view(Range2d(w, h));
+/// Draws an arrow from `start`, in worldspace, along `(dx, dy)` in pixels
+fn draw_arrow(renderer: &mut dyn Drawable, start: (f64, f64), (dx, dy): (f64, f64), view: Range2d) {
+    let length = dx.hypot(dy);
+    if length < 1.0 {
+        return;
+    }
+    let (px, py) = pixel_size(renderer);
+    let at = |along: f64, across: f64| {
+        let (ux, uy) = (dx / length, dy / length);
+        (start.0 + (ux * along - uy * across) * px, start.1 + (uy * along + ux * across) * py)
     };
 
+    // short arrows get smaller heads, so there's always some shaft
+    let head = ARROW_HEAD.min(length * 0.4);
+    if let Some((a, b)) = clip_line(start, at(length - head, 0.0), view) {
+        renderer.line(a, b);
+    }
+    let head = clip_polygon(&[at(length, 0.0), at(length - head, head * 0.45), at(length - head, -head * 0.45)], view);
+    if head.len() >= 3 {
+        renderer.polygon(&head);
+    }
+}
+
+fn draw_quiver(renderer: &mut dyn Drawable, q: &QuiverShape, view: Range2d) {
+    // arrows are measured against the plot area, which is the view in pixels
+    let (px, py) = pixel_size(renderer);
+    let unit = (view.0.size() / px).min(view.1.size() / py) * q.scale;
+    for a in &q.arrows {
+        renderer.set_color(f32_4_to_color(a.color));
+        draw_arrow(renderer, a.start, (a.vector.0 * unit, a.vector.1 * unit), view);
+    }
+}
 
-    update_frame(renderer);
+/// Draws the reference arrows of the quiver plots in `series`, stacked in the top right corner of `view`
+fn draw_quiver_keys(renderer: &mut dyn Drawable, series: &Series, view: Range2d) {
+    let textcol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+    let (px, py) = pixel_size(renderer);
+    let text_h = renderer.text_size("0").1;
+    let Range2d(w, h) = view;
 
-    'main: loop {
-        let mut update = false;
-        for event in renderer.get_events() {
-            match event {
-                Event::Quit { .. } => break 'main,
+    let keys = series.quivers.iter().filter_map(|q| q.key.map(|k| (k, q.scale)));
+    for (i, ((magnitude, color), scale)) in keys.enumerate() {
+        let length = (w.size() / px).min(h.size() / py) * scale * magnitude;
+        let y = h.max - (PADDING + text_h / 2.0 + i as f64 * (text_h + PADDING / 2.0)) * py;
+        let start = (w.max - PADDING * px - length * px, y);
 
-                Event::KeyDown(keycode) => {
-                    if keycode == 1 {
-                        //Keycode::Escape {
-                        break 'main;
-                    }
-                }
-                Event::MouseScroll(_x, y) => {
-                    let multiplier = (y as f64) / 10.0;
-                    let Range2d(w, h) = renderer.get_view();
-                    let w_offset = w.size() * multiplier;
-                    let new_w = Range {
-                        min: w.min - w_offset,
-                        max: w.max + w_offset,
-                    };
-                    let h_offset = h.size() * multiplier;
-                    let new_h = Range {
-                        min: h.min - h_offset,
-                        max: h.max + h_offset,
-                    };
-                    renderer.set_view(Range2d(new_w, new_h));
-                    update = true;
+        renderer.set_color(f32_4_to_color(color));
+        draw_arrow(renderer, start, (length, 0.0), view);
+        renderer.set_color(textcol);
+        renderer.text((start.0 - PADDING / 2.0 * px, y), &format!("{}", magnitude), HAlign::Right, VAlign::Center);
+    }
+}
+
+/// Width of a colorbar, in pixels
+const COLORBAR_WIDTH: f64 = 16.0;
+
+/// Fills the bins of a density plot, clipped to `view`
+fn draw_density(renderer: &mut dyn Drawable, d: &Density, bins: &Bins, view: Range2d) {
+    let Range2d(w, h) = view;
+    let clamp = |v: f64, r: Range| v.max(r.min).min(r.max);
+    let (min, max) = bins.range;
+
+    for bin in &bins.bins {
+        renderer.set_color(f32_4_to_color(d.colormap.color_in(bin.value, min, max)));
+        match bins.shape {
+            BinShape::Rectangle => {
+                let (x, y) = bin.center;
+                let (hw, hh) = (bins.size.0 / 2.0, bins.size.1 / 2.0);
+                let (a, b) = ((clamp(x - hw, w), clamp(y - hh, h)), (clamp(x + hw, w), clamp(y + hh, h)));
+                if a.0 < b.0 && a.1 < b.1 {
+                    renderer.rectangle(a, b);
                 }
-                Event::Resize(_, _) => {
-                    update = true;
+            }
+            BinShape::Hexagon => {
+                let clipped = clip_polygon(&bins.outline(bin), view);
+                if clipped.len() >= 3 {
+                    renderer.polygon(&clipped);
                 }
-                _ => {}
             }
         }
+    }
+}
 
-        if update {
-            update_frame(renderer);
-        }
+/// The colormap and range of values of the first density plot that wants a colorbar
+fn colorbar(densities: &[Density], bins: &[Bins]) -> Option<(Colormap, (f64, f64))> {
+    densities
+        .iter()
+        .zip(bins)
+        .find(|&(d, b)| d.colorbar && b.range.0.is_finite())
+        .map(|(d, b)| (d.colormap, b.range))
+}
+
+/// Draws a colorbar from the bottom to the top of `h`, with its left edge at `left`, all in worldspace.
+/// The ticks are in the colorbar's own range of values.
+fn draw_colorbar(renderer: &mut dyn Drawable, colormap: Colormap, (min, max): (f64, f64), ticks: &[(f64, Tick)], left: f64, h: Range) {
+    let edgecol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+    let (px, _) = pixel_size(renderer);
+    let right = left + COLORBAR_WIDTH * px;
+
+    // enough strips that neighbouring ones are close to the same color
+    let strips = 64;
+    for i in 0..strips {
+        let t = (i as f64 + 0.5) / strips as f64;
+        let bottom = h.min + h.size() * i as f64 / strips as f64;
+        let top = h.min + h.size() * (i + 1) as f64 / strips as f64;
+        renderer.set_color(f32_4_to_color(colormap.color(t)));
+        renderer.rectangle((left, bottom), (right, top));
+    }
 
-        thread::sleep(Duration::from_millis(16));
+    renderer.set_color(edgecol);
+    renderer.unfilled_rectangle((left, h.min), (right, h.max));
+    for &(value, ref tick) in ticks {
+        let y = if max > min { h.min + (value - min) / (max - min) * h.size() } else { (h.min + h.max) / 2.0 };
+        let length = if tick.major { TICK_LENGTH } else { TICK_LENGTH / 2.0 };
+        renderer.line((right, y), (right + length * px, y));
+        renderer.text((right + (TICK_LENGTH + PADDING / 2.0) * px, y), &tick.label, HAlign::Left, VAlign::Center);
     }
 }
 
-fn get_plot_bounds(plot_builder: &PlotBuilder2D, xs: &Vec<Vec<f64>>, ys: &Vec<Vec<f64>>) -> [f64; 4] {
+/// Draws every plot in `series`, clipped to `view`. The bars start from zero on `y_scale`.
+/// `bins` are the densities of `series` binned for `view`.
+fn draw_series(renderer: &mut dyn Drawable, series: &Series, bins: &[Bins], y_scale: Scale, view: Range2d) {
+    // densities are underneath everything else
+    for (d, b) in series.densities.iter().zip(bins) {
+        draw_density(renderer, d, b, view);
+    }
+
+    for c in &series.contours {
+        draw_contour(renderer, c, view);
+    }
+
+    for (color, xy) in &series.bars {
+        draw_bars(renderer, *color, xy, y_scale.forward(0.0), view);
+    }
+
+    for v in &series.violins {
+        draw_violin(renderer, v, view);
+    }
 
-    let mut max_xs: Vec<f64> = Vec::new();
-    let mut max_ys: Vec<f64> = Vec::new();
-    let mut min_xs: Vec<f64> = Vec::new();
-    let mut min_ys: Vec<f64> = Vec::new();
+    for b in &series.boxes {
+        draw_box(renderer, b, view);
+    }
 
-    // Get the plot extremities
-    for i in 0..xs.len() {
-        max_xs.push(get_max(plot_builder.max_x, &xs[i]));
-        max_ys.push(get_max(plot_builder.max_y, &ys[i]));
+    for c in &series.candles {
+        draw_candles(renderer, c, y_scale, view);
+    }
 
-        min_xs.push(get_min(plot_builder.min_x, &xs[i]));
-        min_ys.push(get_min(plot_builder.min_y, &ys[i]));
+    for stem in &series.stems {
+        draw_stems(renderer, stem, view);
     }
 
-    let plot_bounds: [f64; 4] = [
-        // Apply the plot extremities to the global extremities
-        max_xs.iter().cloned().fold(0. / 0., f64::max),
-        max_ys.iter().cloned().fold(0. / 0., f64::max),
-        min_xs.iter().cloned().fold(0. / 0., f64::min),
-        min_ys.iter().cloned().fold(0. / 0., f64::min),
-    ];
+    for q in &series.quivers {
+        draw_quiver(renderer, q, view);
+    }
 
-    plot_bounds
+    // lines are thinned out to what can be seen at this zoom, so huge series draw quickly,
+    // all at once before any are drawn, so that `use-rayon` can thin them out in parallel
+    let pixel = pixel_size(renderer);
+    let lines: Vec<lod::Line> = (0..series.colors.len())
+        .map(|i| lod::Line {
+            xs: &*series.xs[i],
+            ys: &*series.ys[i],
+            sorted: series.sorted[i],
+        })
+        .collect();
+    let decimated = lod::decimate_all(&lines, view, pixel);
+
+    for (i, points) in decimated.iter().enumerate() {
+        let color = series.colors[i];
+        let color_rgba = f32_4_to_color(color);
+        renderer.set_color(color_rgba);
+
+        // values the scale can't show leave a gap in the line
+        for run in clip_polyline(points, view) {
+            renderer.polyline(&run, 2);
+        }
+    }
+
+    draw_quiver_keys(renderer, series, view);
 }
 
-impl Plot {
-    pub fn new2d(mut plot_builder: PlotBuilder2D, mut renderer: Box<Drawable>) {
-        let mut pvs = Vec::new();
+/// Sets up a "fake" view that stretches `view` from `plot_area`, in pixels, out to the edges of a window of size `window`.
+/// Drawing with the fake view puts the real view inside the plot area, and leaves room around it for labels.
+fn stretch_view(view: Range2d, plot_area: Range2d, window: (f64, f64)) -> Range2d {
+    let Range2d(w, h) = view;
+    let px = w.size() / plot_area.0.size();
+    let py = h.size() / plot_area.1.size();
+    Range2d(
+        Range {
+            min: w.min - plot_area.0.min * px,
+            max: w.max + (window.0 - plot_area.0.max) * px,
+        },
+        Range {
+            min: h.min - (window.1 - plot_area.1.max) * py,
+            max: h.max + plot_area.1.min * py,
+        },
+    )
+}
 
-        mem::swap(&mut plot_builder.pvs, &mut pvs);
+/// Draws polar axes inside `area`, which is given in pixels from the top left of the window.
+/// The disk is kept round by drawing it in the largest square that fits.
+fn draw_polar_axes(renderer: &mut dyn Drawable, axes: &Axes, p: &PolarAxes, area: Range2d) -> Option<Range2d> {
+    let bgcol = f32_4_to_color([1.0, 1.0, 1.0, 1.0]);
+    let gridcol = f32_4_to_color([0.85, 0.85, 0.85, 1.0]);
+    let textcol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+    let bordercol = [0, 0, 255, 255];
 
-        let mut colors: Vec<[f32; 4]> = Vec::new();
-        let mut x_points: Vec<Vec<f64>> = Vec::new();
-        let mut y_points: Vec<Vec<f64>> = Vec::new();
+    let view = axes.view;
+    let Range2d(area_w, area_h) = area;
 
-        for pv in pvs.drain(..) {
-            match pv {
-                PlotVals2D::XyColor(ref col, ref xy) => {
-                    set_xy(xy, &mut x_points, &mut y_points);
-                    colors.push(col.clone());
-                }
-                _ => (),
+    // the angle labels go all the way around the disk
+    let text_h = renderer.text_size("0").1;
+    let title_h = if axes.title.is_some() { text_h + PADDING / 2.0 } else { 0.0 };
+    let margin_x = PADDING + renderer.text_size("360\u{b0}").0 + TICK_LENGTH;
+    let margin_y = PADDING + text_h + TICK_LENGTH;
+
+    let side = (area_w.size() - 2.0 * margin_x).min(area_h.size() - 2.0 * margin_y - title_h);
+    if side <= 0.0 {
+        return None;
+    }
+    let center = ((area_w.min + area_w.max) / 2.0, (area_h.min + title_h + area_h.max) / 2.0);
+    let plot_area = Range2d(
+        Range {
+            min: center.0 - side / 2.0,
+            max: center.0 + side / 2.0,
+        },
+        Range {
+            min: center.1 - side / 2.0,
+            max: center.1 + side / 2.0,
+        },
+    );
+
+    renderer.set_view(stretch_view(view, plot_area, renderer.get_size()));
+    let px = view.0.size() / side;
+    let py = view.1.size() / side;
+
+    let disk = polar::circle(1.0);
+    let draw_circle = |renderer: &mut dyn Drawable, points: &[(f64, f64)]| {
+        for i in 0..points.len() {
+            if let Some((a, b)) = clip_line(points[i], points[(i + 1) % points.len()], view) {
+                renderer.line(a, b);
+            }
+        }
+    };
+
+    let background = clip_polygon(&disk, view);
+    if background.len() >= 3 {
+        renderer.set_color(bgcol);
+        renderer.polygon(&background);
+    }
+
+    // circles at round radii, and spokes at round angles
+    let (min_r, max_r) = (p.polar.min_r, p.max_r);
+    let r_ticks: Vec<(f64, Tick)> = Scale::Linear
+        .ticks(min_r, max_r, (side / 120.0) as usize)
+        .into_iter()
+        .map(|t| ((t.value - min_r) / (max_r - min_r), t))
+        .filter(|&(radius, _)| radius > 0.0 && radius <= 1.0)
+        .collect();
+    let step = if p.polar.angle_step > 0.0 { p.polar.angle_step } else { 45.0 };
+    let angles: Vec<f64> = (0..(360.0 / step).ceil() as usize).map(|i| i as f64 * step).collect();
+
+    renderer.set_color(gridcol);
+    if axes.y_gridlines {
+        for &(radius, _) in &r_ticks {
+            draw_circle(renderer, &polar::circle(radius));
+        }
+    }
+    if axes.x_gridlines {
+        for &degrees in &angles {
+            let direction = p.polar.direction(degrees.to_radians());
+            if let Some((a, b)) = clip_line((0.0, 0.0), (direction.cos(), direction.sin()), view) {
+                renderer.line(a, b);
+            }
+        }
+    }
+
+    for (color, wedge) in &p.wedges {
+        let clipped = clip_polygon(&clip_polygon_convex(wedge, &disk), view);
+        if clipped.len() >= 3 {
+            renderer.set_color(f32_4_to_color(*color));
+            renderer.polygon(&clipped);
+        }
+    }
+
+    for (color, points) in &p.lines {
+        renderer.set_color(f32_4_to_color(*color));
+        let clip = |a, b| polar::clip_to_disk(a, b, 1.0).and_then(|(a, b)| clip_line(a, b, view));
+        for run in clip_runs(points, clip) {
+            renderer.polyline(&run, 2);
+        }
+    }
+
+    renderer.set_color(bordercol);
+    draw_circle(renderer, &disk);
+
+    renderer.set_color(textcol);
+    if axes.x_axis {
+        for &degrees in &angles {
+            let direction = p.polar.direction(degrees.to_radians());
+            let (cos, sin) = (direction.cos(), direction.sin());
+            if !view.contains((cos, sin)) {
+                continue;
+            }
+
+            // labels sit just outside the disk, aligned away from it
+            let halign = if cos > 0.3 { HAlign::Left } else if cos < -0.3 { HAlign::Right } else { HAlign::Center };
+            let valign = if sin > 0.3 { VAlign::Bottom } else if sin < -0.3 { VAlign::Top } else { VAlign::Center };
+            let pos = (cos * (1.0 + TICK_LENGTH * px), sin * (1.0 + TICK_LENGTH * py));
+            renderer.text(pos, &polar::format_angle(degrees), halign, valign);
+        }
+    }
+    if axes.y_axis {
+        // the radii are labeled along the spoke halfway between the first two
+        let direction = p.polar.direction((step / 2.0).to_radians());
+        for (radius, tick) in &r_ticks {
+            let pos = (radius * direction.cos(), radius * direction.sin());
+            if view.contains(pos) {
+                renderer.text(pos, &tick.label, HAlign::Left, VAlign::Bottom);
+            }
+        }
+    }
+
+    if let Some(ref title) = axes.title {
+        let w_mid = (view.0.min + view.0.max) / 2.0;
+        let above = view.1.max + (margin_y - PADDING + PADDING / 2.0) * py;
+        renderer.text((w_mid, above), title, HAlign::Center, VAlign::Bottom);
+    }
+
+    renderer.set_view(view);
+
+    Some(plot_area)
+}
+
+/// Draws the pie charts of `axes` inside `area`, which is given in pixels from the top left of the window.
+/// Pie charts always fit the whole disk in the plot area, so zooming and panning leave them where they are.
+fn draw_pie_axes(renderer: &mut dyn Drawable, axes: &Axes, area: Range2d) -> Option<Range2d> {
+    let edgecol = f32_4_to_color([1.0, 1.0, 1.0, 1.0]);
+    let textcol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+    let percentcol = f32_4_to_color([1.0, 1.0, 1.0, 1.0]);
+
+    let Range2d(area_w, area_h) = area;
+
+    // the labels go around the outside of the pies
+    let text_h = renderer.text_size("0").1;
+    let title_h = if axes.title.is_some() { text_h + PADDING / 2.0 } else { 0.0 };
+    let label_w = axes
+        .pies
+        .iter()
+        .filter(|p| p.labels)
+        .flat_map(|p| p.slices.iter().map(|s| renderer.text_size(&s.0).0))
+        .fold(0.0, f64::max);
+    let margin_x = PADDING + label_w + TICK_LENGTH;
+    let margin_y = PADDING + text_h + TICK_LENGTH;
+
+    let side = (area_w.size() - 2.0 * margin_x).min(area_h.size() - 2.0 * margin_y - title_h);
+    if side <= 0.0 {
+        return None;
+    }
+    let center = ((area_w.min + area_w.max) / 2.0, (area_h.min + title_h + area_h.max) / 2.0);
+    let plot_area = Range2d(
+        Range {
+            min: center.0 - side / 2.0,
+            max: center.0 + side / 2.0,
+        },
+        Range {
+            min: center.1 - side / 2.0,
+            max: center.1 + side / 2.0,
+        },
+    );
+
+    // exploded slices stick out past the unit disk, so make room for them
+    let extent = axes
+        .pies
+        .iter()
+        .flat_map(|p| (0..p.slices.len()).map(move |i| p.radius + p.offset(i)))
+        .fold(1.0, f64::max);
+    let view = Range2d(Range { min: -extent, max: extent }, Range { min: -extent, max: extent });
+    renderer.set_view(stretch_view(view, plot_area, renderer.get_size()));
+    let px = 2.0 * extent / side;
+
+    for p in &axes.pies {
+        let fractions = p.fractions();
+        for (i, &(start, end)) in p.angles().iter().enumerate() {
+            if fractions[i] <= 0.0 {
+                continue;
+            }
+            let mid = (start + end) / 2.0;
+            let (cos, sin) = (mid.cos(), mid.sin());
+            let offset = p.offset(i);
+            let middle = (offset * cos, offset * sin);
+            let (start, end) = (start.min(end), start.max(end));
+
+            renderer.set_color(f32_4_to_color(p.color(i)));
+            renderer.wedge(middle, p.inner_radius, p.radius, start, end);
+
+            // thin gaps between the slices keep neighbours with similar colors apart
+            renderer.set_color(edgecol);
+            for &angle in &[start, end] {
+                let inner = (middle.0 + p.inner_radius * angle.cos(), middle.1 + p.inner_radius * angle.sin());
+                let outer = (middle.0 + p.radius * angle.cos(), middle.1 + p.radius * angle.sin());
+                renderer.line(inner, outer);
+            }
+
+            if p.labels {
+                let halign = if cos > 0.3 { HAlign::Left } else if cos < -0.3 { HAlign::Right } else { HAlign::Center };
+                let valign = if sin > 0.3 { VAlign::Bottom } else if sin < -0.3 { VAlign::Top } else { VAlign::Center };
+                let radius = p.radius + TICK_LENGTH * px;
+                renderer.set_color(textcol);
+                renderer.text((middle.0 + radius * cos, middle.1 + radius * sin), &p.slices[i].0, halign, valign);
+            }
+            if p.percentages {
+                let radius = (p.inner_radius.max(0.0) + p.radius) / 2.0;
+                renderer.set_color(percentcol);
+                renderer.text(
+                    (middle.0 + radius * cos, middle.1 + radius * sin),
+                    &pie::format_percentage(fractions[i]),
+                    HAlign::Center,
+                    VAlign::Center,
+                );
             }
         }
+    }
+
+    if let Some(ref title) = axes.title {
+        let above = extent + (margin_y - PADDING + PADDING / 2.0) * px;
+        renderer.set_color(textcol);
+        renderer.text((0.0, above), title, HAlign::Center, VAlign::Bottom);
+    }
+
+    renderer.set_view(view);
+
+    Some(plot_area)
+}
+
+/// Draws `axes` inside `area`, which is given in pixels from the top left of the window.
+/// Returns the part of `area` that the plot itself covers, inside the margins for the labels.
+fn draw_axes(renderer: &mut dyn Drawable, axes: &Axes, area: Range2d) -> Option<Range2d> {
+    if let Some(ref p) = axes.polar {
+        return draw_polar_axes(renderer, axes, p, area);
+    }
+    if !axes.pies.is_empty() {
+        return draw_pie_axes(renderer, axes, area);
+    }
+
+    let bgcol = f32_4_to_color([1.0, 1.0, 1.0, 1.0]);
+    let gridcol = f32_4_to_color([0.85, 0.85, 0.85, 1.0]);
+    let textcol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+
+    let view = axes.view;
+    let Range2d(w, h) = view;
+    let (win_w, win_h) = renderer.get_size();
+    let Range2d(area_w, area_h) = area;
+
+    let x_ticks = visible_ticks(axes.x_scale, &axes.x_kind, w, area_w.size(), 100.0);
+    let y_ticks = visible_ticks(axes.y_scale, &axes.y_kind, h, area_h.size(), 60.0);
+    let y2_ticks = match axes.y2 {
+        Some(ref y2) if y2.axis => visible_ticks(y2.scale, &AxisKind::Numeric, y2.view, area_h.size(), 60.0),
+        _ => vec![],
+    };
+
+    // densities are binned for this view, and the first one that wants it gets a colorbar
+    let bins: Vec<Bins> = axes.series.densities.iter().map(|d| d.bin(view)).collect();
+    let bins2: Vec<Bins> = match axes.y2 {
+        Some(ref y2) => y2.series.densities.iter().map(|d| d.bin(Range2d(w, y2.view))).collect(),
+        None => vec![],
+    };
+    let colorbar = colorbar(&axes.series.densities, &bins).or_else(|| match axes.y2 {
+        Some(ref y2) => colorbar(&y2.series.densities, &bins2),
+        None => None,
+    });
+    let colorbar_ticks = match colorbar {
+        Some((_, (min, max))) => {
+            let range = if max > min { Range { min, max } } else { Range { min: min - 0.5, max: max + 0.5 } };
+            visible_ticks(Scale::Linear, &AxisKind::Numeric, range, area_h.size(), 60.0)
+        }
+        None => vec![],
+    };
+
+    // the margins have to fit the tick labels, the axis labels and the title
+    let text_h = renderer.text_size("0").1;
+    let label_w = y_ticks.iter().map(|t| renderer.text_size(&t.1.label).0).fold(0.0, f64::max);
+    let label_h = x_ticks.iter().map(|t| renderer.text_size(&t.1.label).1).fold(0.0, f64::max);
+    let y2_label_w = y2_ticks.iter().map(|t| renderer.text_size(&t.1.label).0).fold(0.0, f64::max);
+    let y_label_w = if axes.y_label.is_some() { text_h + PADDING / 2.0 } else { 0.0 };
+    let x_label_h = if axes.x_label.is_some() { text_h + PADDING / 2.0 } else { 0.0 };
+    let title_h = if axes.title.is_some() { text_h + PADDING / 2.0 } else { 0.0 };
+
+    let left = PADDING + y_label_w + if axes.y_axis { label_w + TICK_LENGTH + PADDING / 2.0 } else { 0.0 };
+    let bottom = PADDING + x_label_h + if axes.x_axis { label_h + TICK_LENGTH + PADDING / 2.0 } else { 0.0 };
+    let right = match axes.y2 {
+        Some(ref y2) => {
+            let label = if y2.label.is_some() { text_h + PADDING / 2.0 } else { 0.0 };
+            PADDING + label + if y2.axis { y2_label_w + TICK_LENGTH + PADDING / 2.0 } else { 0.0 }
+        }
+        None => PADDING * 2.0,
+    };
+    let colorbar_left = right;
+    let right = match colorbar {
+        Some(_) => {
+            let label_w = colorbar_ticks.iter().map(|t| renderer.text_size(&t.1.label).0).fold(0.0, f64::max);
+            right + COLORBAR_WIDTH + TICK_LENGTH + PADDING / 2.0 + label_w + PADDING
+        }
+        None => right,
+    };
+    let top = PADDING + title_h;
+
+    let plot_area = Range2d(
+        Range {
+            min: area_w.min + left,
+            max: area_w.max - right,
+        },
+        Range {
+            min: area_h.min + top,
+            max: area_h.max - bottom,
+        },
+    );
+    let plot_w = plot_area.0.size();
+    let plot_h = plot_area.1.size();
+    if plot_w <= 0.0 || plot_h <= 0.0 {
+        return None;
+    }
+
+    // worldspace size of a pixel, so the margins can be expressed in worldspace
+    let px = w.size() / plot_w;
+    let py = h.size() / plot_h;
+
+    renderer.set_view(stretch_view(view, plot_area, (win_w, win_h)));
+
+    // the borders are just the edges of the real view
+    let border_min = (w.min, h.min);
+    let border_max = (w.max, h.max);
+
+    draw_borders(bgcol, border_min, border_max, renderer);
+
+    renderer.set_color(gridcol);
+    if axes.x_gridlines {
+        for &(x, ref tick) in &x_ticks {
+            if tick.major {
+                renderer.line((x, h.min), (x, h.max));
+            }
+        }
+    }
+    if axes.y_gridlines {
+        for &(y, ref tick) in &y_ticks {
+            if tick.major {
+                renderer.line((w.min, y), (w.max, y));
+            }
+        }
+    }
+
+    draw_series(renderer, &axes.series, &bins, axes.y_scale, view);
+
+    renderer.set_color(textcol);
+    let tick_label_gap = TICK_LENGTH + PADDING / 2.0;
+    if axes.x_axis {
+        for &(x, ref tick) in &x_ticks {
+            let length = if tick.major { TICK_LENGTH } else { TICK_LENGTH / 2.0 };
+            renderer.line((x, h.min), (x, h.min - length * py));
+            renderer.text((x, h.min - tick_label_gap * py), &tick.label, HAlign::Center, VAlign::Top);
+        }
+    }
+    if axes.y_axis {
+        for &(y, ref tick) in &y_ticks {
+            let length = if tick.major { TICK_LENGTH } else { TICK_LENGTH / 2.0 };
+            renderer.line((w.min, y), (w.min - length * px, y));
+            renderer.text((w.min - tick_label_gap * px, y), &tick.label, HAlign::Right, VAlign::Center);
+        }
+    }
+
+    let w_mid = (w.min + w.max) / 2.0;
+    let h_mid = (h.min + h.max) / 2.0;
+    if let Some(ref title) = axes.title {
+        renderer.text((w_mid, h.max + PADDING / 2.0 * py), title, HAlign::Center, VAlign::Bottom);
+    }
+    if let Some(ref x_label) = axes.x_label {
+        let below_ticks = if axes.x_axis { label_h + tick_label_gap } else { 0.0 };
+        renderer.text((w_mid, h.min - (below_ticks + PADDING / 2.0) * py), x_label, HAlign::Center, VAlign::Top);
+    }
+    if let Some(ref y_label) = axes.y_label {
+        let left_of_ticks = if axes.y_axis { label_w + tick_label_gap } else { 0.0 };
+        renderer.vertical_text((w.min - (left_of_ticks + PADDING / 2.0) * px, h_mid), y_label, HAlign::Right, VAlign::Center);
+    }
+
+    // the secondary axis gets its own view, sharing only the x range with the main one
+    if let Some(ref y2) = axes.y2 {
+        let view2 = Range2d(w, y2.view);
+        renderer.set_view(stretch_view(view2, plot_area, (win_w, win_h)));
+        draw_series(renderer, &y2.series, &bins2, y2.scale, view2);
+
+        renderer.set_color(textcol);
+        for &(y, ref tick) in &y2_ticks {
+            let length = if tick.major { TICK_LENGTH } else { TICK_LENGTH / 2.0 };
+            renderer.line((w.max, y), (w.max + length * px, y));
+            renderer.text((w.max + tick_label_gap * px, y), &tick.label, HAlign::Left, VAlign::Center);
+        }
+        if let Some(ref label) = y2.label {
+            let right_of_ticks = if y2.axis { y2_label_w + tick_label_gap } else { 0.0 };
+            let mid = (y2.view.min + y2.view.max) / 2.0;
+            renderer.vertical_text((w.max + (right_of_ticks + PADDING / 2.0) * px, mid), label, HAlign::Left, VAlign::Center);
+        }
+    }
+
+    if let Some((colormap, range)) = colorbar {
+        renderer.set_view(stretch_view(view, plot_area, (win_w, win_h)));
+        draw_colorbar(renderer, colormap, range, &colorbar_ticks, w.max + colorbar_left * px, h);
+    }
+
+    // reset the view to the real view
+    renderer.set_view(view);
+
+    Some(plot_area)
+}
+
+/// The pixel area of a grid cell, with the window split evenly into `rows` by `cols` cells below a title of `top` pixels
+fn cell_area(cell: Cell, rows: usize, cols: usize, size: (f64, f64), top: f64) -> Range2d {
+    let col_w = size.0 / cols as f64;
+    let row_h = (size.1 - top) / rows as f64;
+    Range2d(
+        Range {
+            min: cell.col as f64 * col_w,
+            max: (cell.col + cell.col_span) as f64 * col_w,
+        },
+        Range {
+            min: top + cell.row as f64 * row_h,
+            max: top + (cell.row + cell.row_span) as f64 * row_h,
+        },
+    )
+}
+
+/// Draws a whole frame: the figure title and every set of axes.
+/// Returns the pixel area of each plot, for finding which one the mouse is over.
+fn draw_figure(renderer: &mut dyn Drawable, axes: &[Axes], rows: usize, cols: usize, title: &Option<String>) -> Vec<Option<Range2d>> {
+    let bordercol = f32_4_to_color([0.95, 0.95, 0.95, 1.0]);
+    let textcol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+
+    renderer.set_color(bordercol);
+    renderer.clear();
+
+    let size = renderer.get_size();
+    let mut top = 0.0;
+    if let Some(ref title) = *title {
+        // text is drawn in worldspace, so map the view straight onto the window for a moment
+        let window = Range2d(Range { min: 0.0, max: size.0 }, Range { min: -size.1, max: 0.0 });
+        renderer.set_view(window);
+        renderer.set_color(textcol);
+        renderer.text((size.0 / 2.0, -PADDING), title, HAlign::Center, VAlign::Top);
+        top = PADDING + renderer.text_size(title).1;
+    }
+
+    axes.iter().map(|a| draw_axes(renderer, a, cell_area(a.cell, rows, cols, size, top))).collect()
+}
+
+/// `Selection` is a box being dragged out with the right mouse button, to zoom into
+#[derive(Copy, Clone, Debug)]
+struct Selection {
+    /// The axes the box is being dragged on
+    axes: usize,
+
+    /// The corners of the box, in pixels from the top left of the window
+    start: (f64, f64),
+    end: (f64, f64),
+}
+
+/// How far in pixels a selection has to be dragged both ways before letting go zooms into it
+const MIN_SELECTION: f64 = 4.0;
+
+/// The scaled coordinates of a pixel position in the plot area `area`, which shows `view`
+fn pixel_to_view(view: Range2d, area: Range2d, (x, y): (f64, f64)) -> (f64, f64) {
+    let Range2d(w, h) = view;
+    (
+        w.min + (x - area.0.min) / area.0.size() * w.size(),
+        h.max - (y - area.1.min) / area.1.size() * h.size(),
+    )
+}
+
+/// The view of the box between the pixel positions `a` and `b` in the plot area `area`, which shows `view`
+fn box_view(view: Range2d, area: Range2d, a: (f64, f64), b: (f64, f64)) -> Range2d {
+    let (a, b) = (pixel_to_view(view, area, a), pixel_to_view(view, area, b));
+    Range2d(
+        Range {
+            min: a.0.min(b.0),
+            max: a.0.max(b.0),
+        },
+        Range {
+            min: a.1.min(b.1),
+            max: a.1.max(b.1),
+        },
+    )
+}
+
+/// Writes the data value at the scaled coordinate `v` along an axis, precise to about `pixel` in scaled coordinates
+fn cursor_label(scale: Scale, kind: &AxisKind, v: f64, pixel: f64) -> String {
+    let value = scale.inverse(v);
+    match *kind {
+        AxisKind::Numeric => scale::format_number(value, (scale.inverse(v + pixel) - value).abs()),
+        AxisKind::DateTime => datetime::format_time(value),
+        AxisKind::Categorical(ref names) => {
+            let i = v.round();
+            if i >= 0.0 && (i as usize) < names.len() {
+                names[i as usize].clone()
+            } else {
+                String::new()
+            }
+        }
+    }
+}
+
+/// Draws what goes over the plots and follows the mouse: a crosshair labeled with the coordinates under the mouse,
+/// and the box being selected. These are drawn in pixels, with the view mapped straight onto the window.
+fn draw_overlays(renderer: &mut dyn Drawable, axes: &[Axes], plot_areas: &[Option<Range2d>], mouse: Option<(f64, f64)>, selection: Option<Selection>) {
+    let (win_w, win_h) = renderer.get_size();
+    renderer.set_view(Range2d(Range { min: 0.0, max: win_w }, Range { min: -win_h, max: 0.0 }));
+    let flip = |(x, y): (f64, f64)| (x, -y);
+
+    if let Some(s) = selection {
+        renderer.set_color(f32_4_to_color([0.2, 0.4, 0.8, 1.0]));
+        renderer.unfilled_rectangle(flip(s.start), flip(s.end));
+    }
+
+    let mouse = match mouse {
+        Some(mouse) => mouse,
+        None => return,
+    };
+    for (a, area) in axes.iter().zip(plot_areas) {
+        let area = match *area {
+            Some(area) if a.crosshair && a.polar.is_none() && a.pies.is_empty() && area.contains(mouse) => area,
+            _ => continue,
+        };
+
+        renderer.set_color(f32_4_to_color([0.5, 0.5, 0.5, 1.0]));
+        renderer.line(flip((area.0.min, mouse.1)), flip((area.0.max, mouse.1)));
+        renderer.line(flip((mouse.0, area.1.min)), flip((mouse.0, area.1.max)));
+
+        let (x, y) = pixel_to_view(a.view, area, mouse);
+        let (px, py) = (a.view.0.size() / area.0.size(), a.view.1.size() / area.1.size());
+        let label = format!("{}, {}", cursor_label(a.x_scale, &a.x_kind, x, px), cursor_label(a.y_scale, &a.y_kind, y, py));
+
+        // the label goes above and to the right of the mouse, unless that would run off the plot
+        let (label_w, label_h) = renderer.text_size(&label);
+        let gap = PADDING / 2.0;
+        let (lx, halign) = if mouse.0 + gap + label_w > area.0.max { (mouse.0 - gap, HAlign::Right) } else { (mouse.0 + gap, HAlign::Left) };
+        let (ly, valign) = if mouse.1 - gap - label_h < area.1.min { (mouse.1 + gap, VAlign::Top) } else { (mouse.1 - gap, VAlign::Bottom) };
+        renderer.set_color(f32_4_to_color([0.2, 0.2, 0.2, 1.0]));
+        renderer.text(flip((lx, ly)), &label, halign, valign);
+    }
+}
+
+/// Zooms `view` in or out around its center. Zooming happens in scaled coordinates,
+/// so log axes zoom evenly across decades.
+fn zoom_view(view: Range2d, scroll: i32) -> Range2d {
+    let multiplier = (scroll as f64) / 10.0;
+    let Range2d(w, h) = view;
+    let w_offset = w.size() * multiplier;
+    let new_w = Range {
+        min: w.min - w_offset,
+        max: w.max + w_offset,
+    };
+    let h_offset = h.size() * multiplier;
+    let new_h = Range {
+        min: h.min - h_offset,
+        max: h.max + h_offset,
+    };
+    Range2d(new_w, new_h)
+}
+
+/// Moves `view` by a distance in pixels, given the pixel area it is drawn in
+fn pan_view(view: Range2d, area: Range2d, dx: f64, dy: f64) -> Range2d {
+    let Range2d(w, h) = view;
+    let x_offset = dx * w.size() / area.0.size();
+    let y_offset = dy * h.size() / area.1.size();
+
+    // pixels count down from the top of the window
+    Range2d(
+        Range {
+            min: w.min - x_offset,
+            max: w.max - x_offset,
+        },
+        Range {
+            min: h.min + y_offset,
+            max: h.max + y_offset,
+        },
+    )
+}
+
+/// Gives the axes at index `i` a new view, and moves every axis shared with it along
+fn set_view(axes: &mut [Axes], i: usize, view: Range2d) {
+    let (x_group, y_group) = (axes[i].x_group, axes[i].y_group);
+    for a in axes.iter_mut() {
+        if a.x_group == x_group {
+            a.view.0 = view.0;
+        }
+        if a.y_group == y_group {
+            a.view.1 = view.1;
+        }
+    }
+}
+
+/// Maps a range of data values through a scale, or `None` if the scale can't show it
+fn scaled_range(scale: Scale, range: Range) -> Option<Range> {
+    let (min, max) = (scale.forward(range.min), scale.forward(range.max));
+    if min.is_finite() && max.is_finite() && min < max {
+        Some(Range { min, max })
+    } else {
+        None
+    }
+}
+
+/// Tells the linked plots about the view of `axes`
+fn publish_view(link: &Link, axes: &Axes) -> u64 {
+    let Range2d(w, h) = axes.view;
+    let unscale = |scale: Scale, r: Range| Range {
+        min: scale.inverse(r.min),
+        max: scale.inverse(r.max),
+    };
+    link.publish(unscale(axes.x_scale, w), unscale(axes.y_scale, h))
+}
+
+/// Whether the axes `i` are linked to the other windows, which are the first axes and those sharing an axis with them
+fn is_linked(axes: &[Axes], i: usize) -> bool {
+    axes[i].x_group == axes[0].x_group || axes[i].y_group == axes[0].y_group
+}
+
+/// Moves `range` the way `from` moved to become `to`, so a secondary axis keeps its place against its primary one
+fn follow_range(from: Range, to: Range, range: Range) -> Range {
+    if from.size() == 0.0 {
+        return range;
+    }
+    let scale = range.size() / from.size();
+    Range {
+        min: range.min + (to.min - from.min) * scale,
+        max: range.max + (to.max - from.max) * scale,
+    }
+}
+
+/// Takes on the view of the linked plots, if it changed. Returns whether anything changed.
+/// Only the axes in the share groups of the first axes follow, and their secondary y-axes move along with them.
+fn follow_link(link: &Link, seen: &mut u64, axes: &mut [Axes]) -> bool {
+    let (x, y) = match link.changes(seen) {
+        Some(ranges) => ranges,
+        None => return false,
+    };
+    let (x_group, y_group) = match axes.first() {
+        Some(a) => (a.x_group, a.y_group),
+        None => return true,
+    };
+    for a in axes.iter_mut() {
+        if let Some(w) = x.filter(|_| a.x_group == x_group).and_then(|x| scaled_range(a.x_scale, x)) {
+            a.view.0 = w;
+        }
+        if let Some(h) = y.filter(|_| a.y_group == y_group).and_then(|y| scaled_range(a.y_scale, y)) {
+            let from = a.view.1;
+            a.change_y2(|v| Range2d(v.0, follow_range(from, h, v.1)));
+            a.view.1 = h;
+        }
+    }
+    true
+}
+
+/// The key that resets the view
+pub const RESET_KEY: i32 = 'r' as i32;
+
+/// Draws the whole figure and keeps it as a layer, then draws the overlays on top of it.
+/// Returns the plot area of each of the axes.
+fn draw_frame(renderer: &mut dyn Drawable, axes: &[Axes], rows: usize, cols: usize, title: &Option<String>, mouse: Option<(f64, f64)>, selection: Option<Selection>) -> Vec<Option<Range2d>> {
+    let plot_areas = draw_figure(renderer, axes, rows, cols, title);
+    renderer.cache_layer();
+    draw_overlays(renderer, axes, &plot_areas, mouse, selection);
+    renderer.present();
+    plot_areas
+}
+
+fn draw_plots(renderer: &mut dyn Drawable, mut axes: Vec<Axes>, rows: usize, cols: usize, title: Option<String>, link: Option<Link>, redraw: Redraw) {
+    let mut mouse = None;
+    let mut selection: Option<Selection> = None;
+    let mut plot_areas = draw_frame(renderer, &axes, rows, cols, &title, mouse, selection);
+
+    // the axes being dragged, and where the mouse was last
+    let mut drag: Option<(usize, (f64, f64))> = None;
+
+    // the overlays only need drawing again as the mouse moves if there are any
+    let crosshairs = axes.iter().any(|a| a.crosshair);
+
+    let mut seen = 0;
+
+    // linked plots and `Redraw`s wake this one up, unless the renderer can't be woken,
+    // in which case it has to look at them every so often
+    let mut timeout = None;
+    let mut waker = None;
+    match renderer.waker() {
+        Some(w) => waker = link.as_ref().map(|link| link.add_waker(w)),
+        None => timeout = Some(draw::POLL_INTERVAL),
+    }
+
+    'main: loop {
+        let mut update = false;
+
+        // the axes whose view the user changed
+        let mut changed = None;
+
+        // whether only the overlays need drawing again
+        let mut overlay = false;
+
+        for event in renderer.wait_events(timeout) {
+            // the plot under the mouse, or the only plot if there is just the one
+            let under = |pt: Option<(f64, f64)>| match pt {
+                _ if axes.len() == 1 => Some(0),
+                Some(pt) => plot_areas.iter().position(|area| area.is_some_and(|a| a.contains(pt))),
+                None => None,
+            };
+
+            match event {
+                Event::Quit => break 'main,
+
+                Event::KeyDown(keycode) => {
+                    if keycode == 1 {
+                        //Keycode::Escape {
+                        break 'main;
+                    } else if keycode == RESET_KEY && !axes.is_empty() {
+                        for a in axes.iter_mut() {
+                            a.view = a.home;
+                            if let Some(ref mut y2) = a.y2 {
+                                y2.view = y2.home;
+                            }
+                        }
+                        changed = Some(0);
+                    }
+                }
+                Event::MouseDown(MouseButton::Left, x, y) => {
+                    drag = under(Some((x, y))).map(|i| (i, (x, y)));
+                }
+                Event::MouseUp(MouseButton::Left, _, _) => {
+                    drag = None;
+                }
+                Event::MouseDown(MouseButton::Right, x, y) => {
+                    selection = under(Some((x, y)))
+                        .filter(|&i| axes[i].polar.is_none() && axes[i].pies.is_empty())
+                        .map(|i| Selection { axes: i, start: (x, y), end: (x, y) });
+                }
+                Event::MouseUp(MouseButton::Right, _, _) => {
+                    // letting go zooms into the box, unless it is too small to have been meant
+                    if let Some(s) = selection.take() {
+                        if let Some(area) = plot_areas[s.axes] {
+                            if (s.end.0 - s.start.0).abs() >= MIN_SELECTION && (s.end.1 - s.start.1).abs() >= MIN_SELECTION {
+                                axes[s.axes].change_y2(|v| box_view(v, area, s.start, s.end));
+                                let view = box_view(axes[s.axes].view, area, s.start, s.end);
+                                set_view(&mut axes, s.axes, view);
+                                changed = Some(s.axes);
+                            }
+                        }
+                        overlay = true;
+                    }
+                }
+                Event::MouseMove(button, x, y) => {
+                    mouse = Some((x, y));
+                    overlay |= crosshairs;
+                    if let Some(ref mut s) = selection {
+                        // the box stays inside the plot it was started on
+                        if let Some(area) = plot_areas[s.axes] {
+                            s.end = (x.max(area.0.min).min(area.0.max), y.max(area.1.min).min(area.1.max));
+                            overlay = true;
+                        }
+                    }
+                    if let (Some(MouseButton::Left), Some((i, last))) = (button, drag) {
+                        if let Some(area) = plot_areas[i] {
+                            axes[i].change_y2(|v| pan_view(v, area, x - last.0, y - last.1));
+                            let view = pan_view(axes[i].view, area, x - last.0, y - last.1);
+                            set_view(&mut axes, i, view);
+                            changed = Some(i);
+                        }
+                        drag = Some((i, (x, y)));
+                    }
+                }
+                Event::MouseScroll(_x, y) => {
+                    // only the plot under the mouse zooms
+                    if let Some(i) = under(mouse) {
+                        axes[i].change_y2(|v| zoom_view(v, y));
+                        let view = zoom_view(axes[i].view, y);
+                        set_view(&mut axes, i, view);
+                        changed = Some(i);
+                    }
+                }
+                Event::Resize(_, _) => {
+                    update = true;
+                }
+                _ => {}
+            }
+        }
+
+        update |= redraw.take();
+        if let Some(ref link) = link {
+            match changed {
+                Some(i) if is_linked(&axes, i) => seen = publish_view(link, &axes[0]),
+                _ => update |= follow_link(link, &mut seen, &mut axes),
+            }
+        }
+
+        if update || changed.is_some() {
+            plot_areas = draw_frame(renderer, &axes, rows, cols, &title, mouse, selection);
+        } else if overlay {
+            // the plots themselves haven't changed, so they come back from the cached layer if the renderer kept it
+            if renderer.restore_layer() {
+                draw_overlays(renderer, &axes, &plot_areas, mouse, selection);
+                renderer.present();
+            } else {
+                plot_areas = draw_frame(renderer, &axes, rows, cols, &title, mouse, selection);
+            }
+        }
+    }
+
+    // the window is closed, so the other linked plots stop waking it
+    if let (Some(link), Some(id)) = (link, waker) {
+        link.remove_waker(id);
+    }
+}
+
+/// Turns a user supplied bound into scaled coordinates, dropping it if the scale can't show it
+fn scaled_bound(scale: Scale, bound: Option<f64>) -> Option<f64> {
+    bound.map(|b| scale.forward(b)).filter(|b| b.is_finite())
+}
+
+/// The range an axis covers at first: the user's bounds where they gave them, and the finite `values` otherwise
+fn axis_range(scale: Scale, user_min: Option<f64>, user_max: Option<f64>, values: &[f64]) -> Range {
+    let min = get_min(scaled_bound(scale, user_min), values);
+    let max = get_max(scaled_bound(scale, user_max), values);
+    let (min, max) = finite_range(min, max);
+    Range { min, max }
+}
+
+/// Makes sure a range can be shown: there may be no values at all, or only one
+fn finite_range(min: f64, max: f64) -> (f64, f64) {
+    if !min.is_finite() || !max.is_finite() {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 0.5, max + 0.5)
+    } else {
+        (min, max)
+    }
+}
+
+/// Applies the axis scales to a contour grid, dropping the rows and columns the scales can't show
+fn scale_contour(mut c: Contour, x_scale: Scale, y_scale: Scale) -> Contour {
+    let keep_x: Vec<usize> = (0..c.xs.len()).filter(|&i| x_scale.forward(c.xs[i]).is_finite()).collect();
+    let keep_y: Vec<usize> = (0..c.ys.len()).filter(|&j| y_scale.forward(c.ys[j]).is_finite()).collect();
+
+    // short or ragged rows are filled out with NaN, which leaves a hole
+    let z = |i: usize, j: usize| c.zs.get(j).and_then(|row| row.get(i)).cloned().unwrap_or(f64::NAN);
+    c.zs = keep_y.iter().map(|&j| keep_x.iter().map(|&i| z(i, j)).collect()).collect();
+    c.xs = keep_x.iter().map(|&i| x_scale.forward(c.xs[i])).collect();
+    c.ys = keep_y.iter().map(|&j| y_scale.forward(c.ys[j])).collect();
+    c
+}
+
+impl Series {
+    fn new() -> Series {
+        Series {
+            xs: Vec::new(),
+            ys: Vec::new(),
+            colors: Vec::new(),
+            sorted: Vec::new(),
+            contours: Vec::new(),
+            bars: Vec::new(),
+            boxes: Vec::new(),
+            violins: Vec::new(),
+            quivers: Vec::new(),
+            candles: Vec::new(),
+            stems: Vec::new(),
+            densities: Vec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.colors.is_empty() && self.contours.is_empty() && self.bars.is_empty() && self.boxes.is_empty() && self.violins.is_empty() && self.quivers.is_empty() && self.candles.is_empty() && self.stems.is_empty() && self.densities.is_empty()
+    }
+
+    fn add_boxes(&mut self, positions: &[f64], b: BoxPlot, x_scale: Scale, y_scale: Scale) {
+        for (&x, (_, samples)) in positions.iter().zip(&b.groups) {
+            if let Some(stats) = distribution::box_stats(samples, b.whiskers) {
+                self.boxes.push(BoxShape {
+                    color: b.color,
+                    x: x_scale.forward(x),
+                    half_width: b.width / 2.0,
+                    stats: stats.map(|v| y_scale.forward(v)),
+                    notch: b.notch,
+                    outliers: b.outliers,
+                });
+            }
+        }
+    }
+
+    fn add_violins(&mut self, positions: &[f64], v: Violin, x_scale: Scale, y_scale: Scale) {
+        for (&x, (_, samples)) in positions.iter().zip(&v.groups) {
+            let bandwidth = v.bandwidth.unwrap_or_else(|| distribution::silverman_bandwidth(samples));
+            let density: Vec<(f64, f64)> = distribution::kde(samples, bandwidth, v.points)
+                .into_iter()
+                .map(|(value, density)| (y_scale.forward(value), density))
+                .filter(|p| p.0.is_finite())
+                .collect();
+            let peak = density.iter().map(|p| p.1).fold(0.0, f64::max);
+            if peak <= 0.0 {
+                continue;
+            }
+
+            // up the right side and back down the left side
+            let x = x_scale.forward(x);
+            let half_width = v.width / 2.0 / peak;
+            let mut outline: Vec<(f64, f64)> = density.iter().map(|&(y, d)| (x + d * half_width, y)).collect();
+            outline.extend(density.iter().rev().map(|&(y, d)| (x - d * half_width, y)));
+
+            let quartiles = if v.quartiles { distribution::box_stats(samples, distribution::Whiskers::MinMax) } else { None };
+            self.violins.push(ViolinShape {
+                color: v.color,
+                x,
+                outline,
+                quartiles: quartiles.map(|s| s.map(|v| y_scale.forward(v))),
+            });
+        }
+    }
+
+    fn add_quiver(&mut self, q: Quiver, x_scale: Scale, y_scale: Scale) {
+        let scale = q.auto_scale();
+        let (min, max) = q.magnitude_range();
+        let arrows = q
+            .points
+            .iter()
+            .zip(&q.vectors)
+            .map(|(&(x, y), &(u, v))| {
+                let color = match q.colormap {
+                    Some(colormap) => colormap.color_in(u.hypot(v), min, max),
+                    None => q.color,
+                };
+                Arrow {
+                    start: (x_scale.forward(x), y_scale.forward(y)),
+                    vector: (u, v),
+                    color,
+                }
+            })
+            .filter(|a| a.start.0.is_finite() && a.start.1.is_finite() && a.vector.0.is_finite() && a.vector.1.is_finite())
+            .collect();
+
+        // the key matches the color of the longest arrows
+        let key_color = match q.colormap {
+            Some(colormap) => colormap.color(1.0),
+            None => q.color,
+        };
+        self.quivers.push(QuiverShape {
+            arrows,
+            scale,
+            key: q.key.map(|k| (k, key_color)),
+        });
+    }
+
+    fn add_candles(&mut self, mut c: Candles, x_scale: Scale, y_scale: Scale) {
+        let volume = c.style == OhlcStyle::Volume;
+        c.records = c
+            .records
+            .iter()
+            .filter(|r| r.is_finite() && (!volume || r.volume.is_some_and(f64::is_finite)))
+            .map(|r| Ohlc {
+                time: x_scale.forward(r.time),
+                open: y_scale.forward(r.open),
+                high: y_scale.forward(r.high),
+                low: y_scale.forward(r.low),
+                close: y_scale.forward(r.close),
+                volume: r.volume.map(|v| y_scale.forward(v)),
+            })
+            .filter(|r| r.is_finite())
+            .collect();
+        c.records.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+        // missing records leave gaps, rather than making their neighbours wider
+        let step = c.records.windows(2).map(|p| p[1].time - p[0].time).filter(|&d| d > 0.0).fold(f64::INFINITY, f64::min);
+        let half_width = if step.is_finite() { step } else { 1.0 } * c.width / 2.0;
+        self.candles.push(CandleShape { candles: c, half_width });
+    }
+
+    /// Adds a line through the already scaled `xs` and `ys`
+    fn add_line(&mut self, color: [f32; 4], xs: Column, ys: Column) {
+        self.sorted.push(lod::is_sorted(&xs));
+        self.colors.push(color);
+        self.xs.push(xs);
+        self.ys.push(ys);
+    }
+
+    /// Adds a plot, transforming its values by the axis scales
+    fn add(&mut self, pv: PlotVals2D, x_scale: Scale, y_scale: Scale) {
+        match pv {
+            PlotVals2D::XyColor(col, xy) => {
+                let (xs, ys): (Vec<f64>, Vec<f64>) = xy.into_iter().map(|(x, y)| (x_scale.forward(x), y_scale.forward(y))).unzip();
+                self.add_line(col, xs.into(), ys.into());
+            }
+            PlotVals2D::ColumnsColor(col, xs, ys) => self.add_line(col, scale_column(xs, x_scale), scale_column(ys, y_scale)),
+            PlotVals2D::StepColor(col, step, xy) => {
+                // the steps go between the scaled points, so mid steps stay halfway on any scale
+                let scaled: Vec<(f64, f64)> = xy.into_iter().map(|(x, y)| (x_scale.forward(x), y_scale.forward(y))).collect();
+                let (xs, ys): (Vec<f64>, Vec<f64>) = step.points(&scaled).into_iter().unzip();
+                self.add_line(col, xs.into(), ys.into());
+            }
+            PlotVals2D::StemColor(color, baseline, xy) => {
+                let points = xy
+                    .into_iter()
+                    .map(|(x, y)| (x_scale.forward(x), y_scale.forward(y)))
+                    .filter(|p| p.0.is_finite() && p.1.is_finite())
+                    .collect();
+                self.stems.push(StemShape {
+                    color,
+                    baseline: y_scale.forward(baseline),
+                    points,
+                });
+            }
+            PlotVals2D::Contour(c) => self.contours.push(scale_contour(c, x_scale, y_scale)),
+            PlotVals2D::Density(mut d) => {
+                // the values stay with their points when points the scales can't show are left out
+                let given = d.values.take();
+                let value = |i: usize| given.as_ref().and_then(|v| v.get(i)).cloned().unwrap_or(f64::NAN);
+                let (points, values): (Vec<(f64, f64)>, Vec<f64>) = d
+                    .points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(x, y))| ((x_scale.forward(x), y_scale.forward(y)), value(i)))
+                    .filter(|&(p, _)| p.0.is_finite() && p.1.is_finite())
+                    .unzip();
+                d.values = given.as_ref().map(|_| values);
+                d.points = points;
+
+                // the cells of a grid stay evenly spaced between its scaled edges
+                if let Some(ref mut grid) = d.grid {
+                    grid.x = Range { min: x_scale.forward(grid.x.min), max: x_scale.forward(grid.x.max) };
+                    grid.y = Range { min: y_scale.forward(grid.y.min), max: y_scale.forward(grid.y.max) };
+                }
+                self.densities.push(d);
+            }
+            PlotVals2D::BarColor(col, xy) => {
+                let scaled: Vec<(f64, f64)> = xy.into_iter().map(|(x, y)| (x_scale.forward(x), y_scale.forward(y))).collect();
+                self.bars.push((col, scaled));
+            }
+            PlotVals2D::BoxPlot(positions, b) => self.add_boxes(&positions, b, x_scale, y_scale),
+            PlotVals2D::Violin(positions, v) => self.add_violins(&positions, v, x_scale, y_scale),
+            PlotVals2D::Quiver(q) => self.add_quiver(q, x_scale, y_scale),
+            PlotVals2D::Candles(c) => self.add_candles(c, x_scale, y_scale),
+            PlotVals2D::SecondaryY(pv) => self.add(*pv, x_scale, y_scale),
+
+            // pies take up the whole of their axes, and are drawn from the plot builder instead.
+            // Functions aren't drawn yet.
+            PlotVals2D::Pie(_) | PlotVals2D::FunColor(..) | PlotVals2D::AnimFunColor(..) => (),
+        }
+    }
+
+    /// The x and y values that the axes should cover at first
+    fn extent(&self, y_scale: Scale) -> (Vec<f64>, Vec<f64>) {
+        // lines only pass on their bounds, rather than a copy of all of their values
+        let mut xs: Vec<f64> = self.xs.iter().flat_map(column_bounds).collect();
+        let mut ys: Vec<f64> = self.ys.iter().flat_map(column_bounds).collect();
+
+        // contours only take part through their grid coordinates
+        for c in &self.contours {
+            xs.extend(c.xs.iter().cloned());
+            ys.extend(c.ys.iter().cloned());
+        }
+
+        // bars reach down to zero
+        for (_, xy) in &self.bars {
+            xs.extend(xy.iter().map(|p| p.0));
+            ys.extend(xy.iter().map(|p| p.1).chain(Some(y_scale.forward(0.0))));
+        }
+
+        for b in &self.boxes {
+            xs.extend(&[b.x - b.half_width, b.x + b.half_width]);
+            ys.extend(&[b.stats.whisker_low, b.stats.whisker_high]);
+            if b.outliers {
+                ys.extend(b.stats.outliers.iter().cloned());
+            }
+        }
+
+        for v in &self.violins {
+            xs.extend(v.outline.iter().map(|p| p.0));
+            ys.extend(v.outline.iter().map(|p| p.1));
+        }
+
+        for d in &self.densities {
+            xs.extend(d.points.iter().map(|p| p.0));
+            ys.extend(d.points.iter().map(|p| p.1));
+            if let Some(ref grid) = d.grid {
+                xs.extend(&[grid.x.min, grid.x.max]);
+                ys.extend(&[grid.y.min, grid.y.max]);
+            }
+        }
+
+        // stems reach to their baseline
+        for stem in &self.stems {
+            xs.extend(stem.points.iter().map(|p| p.0));
+            ys.extend(stem.points.iter().map(|p| p.1).chain(Some(stem.baseline)));
+        }
+
+        for c in &self.candles {
+            for r in &c.candles.records {
+                xs.extend(&[r.time - c.half_width, r.time + c.half_width]);
+                match c.candles.style {
+                    OhlcStyle::Volume => ys.extend(&[y_scale.forward(0.0), r.volume.unwrap_or(f64::NAN)]),
+                    _ => ys.extend(&[r.low, r.high]),
+                }
+            }
+        }
+
+        // arrows are measured on screen, so only where they start takes part
+        for q in &self.quivers {
+            xs.extend(q.arrows.iter().map(|a| a.start.0));
+            ys.extend(q.arrows.iter().map(|a| a.start.1));
+        }
+
+        (xs, ys)
+    }
+}
+
+/// Takes a plot off the secondary y-axis, for axes that only have one
+fn primary(pv: PlotVals2D) -> PlotVals2D {
+    match pv {
+        PlotVals2D::SecondaryY(pv) => primary(*pv),
+        pv => pv,
+    }
+}
+
+impl PolarAxes {
+    fn new(polar: Polar, pvs: Vec<PlotVals2D>) -> PolarAxes {
+        let mut lines = Vec::new();
+        let mut bars = Vec::new();
+        for pv in pvs.into_iter().map(primary) {
+            match pv {
+                PlotVals2D::XyColor(col, xy) => lines.push((col, xy)),
+                PlotVals2D::ColumnsColor(col, xs, ys) => {
+                    let n = xs.len().min(ys.len());
+                    lines.push((col, (0..n).map(|i| (xs.at(i), ys.at(i))).collect()));
+                }
+                PlotVals2D::BarColor(col, xy) => bars.push((col, xy)),
+                _ => (),
+            }
+        }
+
+        let radii: Vec<f64> = lines.iter().chain(&bars).flat_map(|l| l.1.iter().map(|p| p.1)).collect();
+        let max_r = get_max(polar.max_r, &radii);
+        let max_r = if max_r > polar.min_r { max_r } else { polar.min_r + 1.0 };
+
+        let lines = lines
+            .into_iter()
+            .map(|(col, xy)| (col, xy.into_iter().map(|(theta, r)| polar.to_disk(theta, r, max_r)).collect()))
+            .collect();
+
+        // rose diagram wedges are 80% of the smallest angle between neighbouring bars wide
+        let mut wedges = Vec::new();
+        for (col, xy) in bars {
+            let mut thetas: Vec<f64> = xy.iter().map(|p| p.0).filter(|t| t.is_finite()).collect();
+            thetas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let gap = thetas.windows(2).map(|p| p[1] - p[0]).filter(|&d| d > 0.0).fold(f64::consts::FRAC_PI_4, f64::min);
+            let half_width = gap * 0.4;
+
+            for (theta, r) in xy {
+                if theta.is_finite() && r.is_finite() {
+                    let radius = polar.to_disk(0.0, r, max_r);
+                    let (start, end) = (polar.direction(theta - half_width), polar.direction(theta + half_width));
+                    wedges.push((col, polar::wedge(start, end, radius.0.hypot(radius.1))));
+                }
+            }
+        }
+
+        PolarAxes {
+            polar,
+            max_r,
+            lines,
+            wedges,
+        }
+    }
+}
+
+impl Axes {
+    fn new(mut plot_builder: PlotBuilder2D, cell: Cell, x_group: usize, y_group: usize) -> Axes {
+        let mut pvs = Vec::new();
+
+        mem::swap(&mut plot_builder.pvs, &mut pvs);
+
+        // polar axes take all of the plots, and leave the rest of the axes empty
+        let polar = plot_builder.polar.map(|p| PolarAxes::new(p, mem::take(&mut pvs)));
+
+        // and so do pie charts
+        let mut pies = Vec::new();
+        for pv in &pvs {
+            if let PlotVals2D::Pie(ref pie) = *pv {
+                pies.push(pie.clone());
+            }
+        }
+        if !pies.is_empty() {
+            pvs.clear();
+        }
+
+        // categories are always evenly spaced
+        let linear_if_categorical = |scale: Scale, kind: &AxisKind| match *kind {
+            AxisKind::Categorical(_) => Scale::Linear,
+            _ => scale.checked(),
+        };
+        plot_builder.x_scale = linear_if_categorical(plot_builder.x_scale, &plot_builder.x_kind);
+        plot_builder.y_scale = linear_if_categorical(plot_builder.y_scale, &plot_builder.y_kind);
+        plot_builder.y2_scale = plot_builder.y2_scale.checked();
+        let (x_scale, y_scale, y2_scale) = (plot_builder.x_scale, plot_builder.y_scale, plot_builder.y2_scale);
+
+        let mut series = Series::new();
+        let mut secondary = Series::new();
+        for pv in pvs.drain(..) {
+            match pv {
+                PlotVals2D::SecondaryY(pv) => secondary.add(*pv, x_scale, y2_scale),
+                pv => series.add(pv, x_scale, y_scale),
+            }
+        }
+
+        // each y-axis covers only its own series, while the x-axis covers them all
+        let (mut xs, ys) = series.extent(y_scale);
+        let (xs2, ys2) = secondary.extent(y2_scale);
+        xs.extend(xs2);
+
+        let mut home = Range2d(
+            axis_range(x_scale, plot_builder.min_x, plot_builder.max_x, &xs),
+            axis_range(y_scale, plot_builder.min_y, plot_builder.max_y, &ys),
+        );
+
+        // categorical axes show every category with half a unit to spare on either side
+        if let AxisKind::Categorical(ref names) = plot_builder.x_kind {
+            home.0.max = plot_builder.max_x.unwrap_or(names.len() as f64 - 0.5);
+            home.0.min = plot_builder.min_x.unwrap_or(-0.5);
+        }
+        if let AxisKind::Categorical(ref names) = plot_builder.y_kind {
+            home.1.max = plot_builder.max_y.unwrap_or(names.len() as f64 - 0.5);
+            home.1.min = plot_builder.min_y.unwrap_or(-0.5);
+        }
+
+        // polar plots and pie charts are placed on the unit disk
+        if polar.is_some() || !pies.is_empty() {
+            home = Range2d(Range { min: -1.0, max: 1.0 }, Range { min: -1.0, max: 1.0 });
+        }
+
+        let y2 = if secondary.is_empty() {
+            None
+        } else {
+            let home = axis_range(y2_scale, plot_builder.min_y2, plot_builder.max_y2, &ys2);
+            Some(SecondaryAxis {
+                series: secondary,
+                scale: y2_scale,
+                label: plot_builder.y2_label,
+                axis: plot_builder.y2_axis,
+                view: home,
+                home,
+            })
+        };
+
+        Axes {
+            series,
+            y2,
+            polar,
+            pies,
+            x_scale,
+            y_scale,
+            x_kind: plot_builder.x_kind,
+            y_kind: plot_builder.y_kind,
+            x_axis: plot_builder.x_axis,
+            y_axis: plot_builder.y_axis,
+            x_gridlines: plot_builder.x_gridlines,
+            crosshair: plot_builder.crosshair,
+            y_gridlines: plot_builder.y_gridlines,
+            title: plot_builder.title,
+            x_label: plot_builder.x_label,
+            y_label: plot_builder.y_label,
+            cell,
+            view: home,
+            home,
+            x_group,
+            y_group,
+        }
+    }
+
+    /// Moves the secondary y-axis the same way `change` moves a view
+    fn change_y2<F: Fn(Range2d) -> Range2d>(&mut self, change: F) {
+        if let Some(ref mut y2) = self.y2 {
+            y2.view = change(Range2d(self.view.0, y2.view)).1;
+        }
+    }
+}
+
+/// Joins the subplots that share an axis into groups, each named by its lowest subplot.
+/// Shares may chain or loop in any way, so the groups are found with union-find.
+fn share_groups(shares: &[Option<usize>]) -> Vec<usize> {
+    fn root(parents: &[usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            i = parents[i];
+        }
+        i
+    }
+
+    let mut parents: Vec<usize> = (0..shares.len()).collect();
+    for (i, share) in shares.iter().enumerate() {
+        if let Some(with) = share.filter(|&with| with < shares.len()) {
+            let (a, b) = (root(&parents, i), root(&parents, with));
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+    (0..shares.len()).map(|i| root(&parents, i)).collect()
+}
+
+/// Makes every shared axis start out covering the home views of all the axes sharing it
+fn unite_homes(axes: &mut [Axes]) {
+    for i in 0..axes.len() {
+        let (x_group, y_group) = (axes[i].x_group, axes[i].y_group);
+        let mut home = axes[i].home;
+        for a in axes.iter() {
+            if a.x_group == x_group {
+                home.0 = Range {
+                    min: home.0.min.min(a.home.0.min),
+                    max: home.0.max.max(a.home.0.max),
+                };
+            }
+            if a.y_group == y_group {
+                home.1 = Range {
+                    min: home.1.min.min(a.home.1.min),
+                    max: home.1.max.max(a.home.1.max),
+                };
+            }
+        }
+        axes[i].home = home;
+        axes[i].view = home;
+    }
+}
+
+impl Plot {
+    pub fn new2d(plot_builder: PlotBuilder2D, renderer: Box<dyn Drawable>, link: Option<Link>, redraw: Redraw) {
+        let mut figure = Figure::new(1, 1);
+        figure.add(0, 0, plot_builder);
+        Plot::new_figure(figure, renderer, link, redraw);
+    }
+
+    pub fn new_figure(figure: Figure, mut renderer: Box<dyn Drawable>, link: Option<Link>, redraw: Redraw) {
+        let x_groups = share_groups(&figure.subplots.iter().map(|s| s.share_x).collect::<Vec<_>>());
+        let y_groups = share_groups(&figure.subplots.iter().map(|s| s.share_y).collect::<Vec<_>>());
+
+        let mut axes: Vec<Axes> = figure
+            .subplots
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| Axes::new(s.plot_builder, s.cell, x_groups[i], y_groups[i]))
+            .collect();
+        unite_homes(&mut axes);
+
+        draw_plots(&mut *renderer, axes, figure.rows, figure.cols, figure.title, link, redraw);
+    }
+
+    pub fn new3d(plot_builder: PlotBuilder3D, mut renderer: Box<dyn Drawable>, redraw: Redraw) {
+        draw_plot3d(&mut *renderer, plot_builder, redraw);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn share_groups_test() {
+        // chains end up in one group, even when they loop back on themselves
+        assert_eq!(share_groups(&[None, Some(0), Some(1), None]), vec![0, 0, 0, 3]);
+        assert_eq!(share_groups(&[Some(1), Some(0), None]), vec![0, 0, 2]);
+        assert_eq!(share_groups(&[Some(2), Some(0), Some(1), Some(3)]), vec![0, 0, 0, 3]);
+    }
+
+    #[test]
+    fn follow_link_test() {
+        use plotter::LinkAxes;
+
+        let cell = Cell { row: 0, col: 0, row_span: 1, col_span: 1 };
+        let mut twin = PlotBuilder2D::new();
+        twin.add_simple_xy(vec![(0.0, 0.0), (1.0, 1.0)]);
+        twin.add_y2(PlotVals2D::XyColor([0.0, 0.0, 0.0, 1.0], vec![(0.0, 0.0), (1.0, 10.0)]));
+        let mut plain = PlotBuilder2D::new();
+        plain.add_simple_xy(vec![(0.0, 0.0), (1.0, 1.0)]);
+
+        // the second axes share x with the first, the third share nothing
+        let mut axes = vec![Axes::new(twin, cell, 0, 0), Axes::new(plain.clone(), cell, 0, 1), Axes::new(plain, cell, 2, 2)];
+        let (y, y2, third) = (axes[0].view.1, axes[0].y2.as_ref().unwrap().view, axes[2].view);
+
+        let link = Link::new(LinkAxes::Both);
+        let (w, h) = (Range { min: 2.0, max: 4.0 }, Range { min: 1.0, max: 2.0 });
+        link.publish(w, h);
+        let mut seen = 0;
+        assert!(follow_link(&link, &mut seen, &mut axes));
+        assert!(!follow_link(&link, &mut seen, &mut axes));
+
+        let ends = |r: Range| (r.min, r.max);
+        assert_eq!((ends(axes[0].view.0), ends(axes[0].view.1)), (ends(w), ends(h)));
+        assert_eq!((ends(axes[1].view.0), ends(axes[1].view.1)), (ends(w), ends(y)));
+        assert_eq!((ends(axes[2].view.0), ends(axes[2].view.1)), (ends(third.0), ends(third.1)));
 
-        // [MAX_X, MAX_Y, MIN_X, MIN_Y]
-        let plot_bounds: [f64; 4] = get_plot_bounds(&plot_builder, &x_points, &y_points);
-        draw_plots(&mut *renderer, &x_points, &y_points, &colors, plot_bounds);
+        // the secondary y-axis moves along by the same share of its height as the y-axis did
+        let moved = axes[0].y2.as_ref().unwrap().view;
+        assert!(((moved.min - y2.min) / y2.size() - (h.min - y.min) / y.size()).abs() < 1e-9);
+        assert!(((moved.max - y2.max) / y2.size() - (h.max - y.max) / y.size()).abs() < 1e-9);
     }
 }
@@ -0,0 +1,358 @@
+//! **plot3d** is the backend that renders 3 dimensional plots.
+//!
+//! Everything is projected through the camera and drawn far to near (the painter's algorithm),
+//! so nearer surfaces and lines cover the ones behind them.
+
+use std::f64;
+
+use camera::{self, Camera};
+use plot::{f32_4_to_color, PADDING, RESET_KEY, TICK_LENGTH};
+use plotbuilder::{PlotBuilder3D, PlotVals3D};
+use scale::{Scale, Tick};
+
+use draw::{Drawable, Event, HAlign, MouseButton, Range, Range2d, VAlign, POLL_INTERVAL};
+use plotter::Redraw;
+
+/// Half the width of the square in the middle of the window that the plot is drawn in, in projected units
+const VIEW_SIZE: f64 = 1.6;
+
+/// Degrees the camera turns per pixel dragged
+const ORBIT_SPEED: f64 = 0.5;
+
+/// The two ends of a line
+type Edge = [[f64; 3]; 2];
+
+/// `Shape` is one piece of a 3D plot, in the cube from -1 to 1 that the data is fitted into
+enum Shape {
+    Line([f32; 4], Edge, u16),
+    Point([f32; 4], [f64; 3]),
+
+    /// A face of a surface, with its normal for shading
+    Face([f32; 4], [[f64; 3]; 4], [f64; 3]),
+}
+
+impl Shape {
+    fn points(&self) -> &[[f64; 3]] {
+        match *self {
+            Shape::Line(_, ref points, _) => points,
+            Shape::Point(_, ref point) => ::std::slice::from_ref(point),
+            Shape::Face(_, ref points, _) => points,
+        }
+    }
+}
+
+/// `Scene` is everything in a 3D plot, ready to be drawn from any camera
+struct Scene {
+    shapes: Vec<Shape>,
+
+    /// The range of the data along each axis
+    ranges: [Range; 3],
+
+    labels: [Option<String>; 3],
+    title: Option<String>,
+    axes: bool,
+}
+
+/// The range of the finite values, widened if it would be empty
+fn finite_range(values: &[f64]) -> Range {
+    let min = values.iter().cloned().filter(|v| v.is_finite()).fold(f64::NAN, f64::min);
+    let max = values.iter().cloned().filter(|v| v.is_finite()).fold(f64::NAN, f64::max);
+    if !min.is_finite() || !max.is_finite() {
+        Range { min: 0.0, max: 1.0 }
+    } else if min == max {
+        Range { min: min - 0.5, max: max + 0.5 }
+    } else {
+        Range { min, max }
+    }
+}
+
+/// Calls `f` with the four corners of every cell of a grid whose corners are all finite
+fn grid_cells<F: FnMut([(f64, f64, f64); 4])>(xs: &[f64], ys: &[f64], zs: &[Vec<f64>], mut f: F) {
+    for j in 0..ys.len().saturating_sub(1) {
+        for i in 0..xs.len().saturating_sub(1) {
+            let corner = |i: usize, j: usize| (xs[i], ys[j], zs.get(j).and_then(|row| row.get(i)).cloned().unwrap_or(f64::NAN));
+            let cell = [corner(i, j), corner(i + 1, j), corner(i + 1, j + 1), corner(i, j + 1)];
+            if cell.iter().all(|p| p.0.is_finite() && p.1.is_finite() && p.2.is_finite()) {
+                f(cell);
+            }
+        }
+    }
+}
+
+/// Calls `f` with each pair of neighbouring grid points along the rows and columns of a grid
+fn grid_lines<F: FnMut((f64, f64, f64), (f64, f64, f64))>(xs: &[f64], ys: &[f64], zs: &[Vec<f64>], mut f: F) {
+    let point = |i: usize, j: usize| (xs[i], ys[j], zs.get(j).and_then(|row| row.get(i)).cloned().unwrap_or(f64::NAN));
+    for j in 0..ys.len() {
+        for i in 0..xs.len() {
+            if i + 1 < xs.len() {
+                f(point(i, j), point(i + 1, j));
+            }
+            if j + 1 < ys.len() {
+                f(point(i, j), point(i, j + 1));
+            }
+        }
+    }
+}
+
+impl Scene {
+    fn new(plot_builder: PlotBuilder3D) -> Scene {
+        // find the ranges first, so everything can be fitted into the cube
+        let (mut xs, mut ys, mut zs) = (Vec::new(), Vec::new(), Vec::new());
+        for pv in &plot_builder.pvs {
+            match *pv {
+                PlotVals3D::LineColor(_, ref xyz) | PlotVals3D::ScatterColor(_, ref xyz) => {
+                    for &(x, y, z) in xyz {
+                        xs.push(x);
+                        ys.push(y);
+                        zs.push(z);
+                    }
+                }
+                PlotVals3D::Wireframe(_, ref gx, ref gy, ref gz) | PlotVals3D::Surface(_, ref gx, ref gy, ref gz) => {
+                    xs.extend(gx.iter().cloned());
+                    ys.extend(gy.iter().cloned());
+                    zs.extend(gz.iter().flat_map(|row| row.iter().cloned()));
+                }
+            }
+        }
+        let ranges = [finite_range(&xs), finite_range(&ys), finite_range(&zs)];
+        let fit = |(x, y, z): (f64, f64, f64)| {
+            let fit = |v: f64, r: Range| 2.0 * (v - r.min) / r.size() - 1.0;
+            [fit(x, ranges[0]), fit(y, ranges[1]), fit(z, ranges[2])]
+        };
+        let finite = |p: &(f64, f64, f64)| p.0.is_finite() && p.1.is_finite() && p.2.is_finite();
+
+        let mut shapes = Vec::new();
+        for pv in plot_builder.pvs {
+            match pv {
+                PlotVals3D::LineColor(color, xyz) => {
+                    for pair in xyz.windows(2) {
+                        if finite(&pair[0]) && finite(&pair[1]) {
+                            shapes.push(Shape::Line(color, [fit(pair[0]), fit(pair[1])], 2));
+                        }
+                    }
+                }
+                PlotVals3D::ScatterColor(color, xyz) => {
+                    shapes.extend(xyz.iter().filter(|p| finite(p)).map(|&p| Shape::Point(color, fit(p))));
+                }
+                PlotVals3D::Wireframe(color, gx, gy, gz) => {
+                    grid_lines(&gx, &gy, &gz, |a, b| {
+                        if finite(&a) && finite(&b) {
+                            shapes.push(Shape::Line(color, [fit(a), fit(b)], 1));
+                        }
+                    });
+                }
+                PlotVals3D::Surface(colormap, gx, gy, gz) => {
+                    let heights = finite_range(&gz.iter().flat_map(|row| row.iter().cloned()).collect::<Vec<f64>>());
+                    grid_cells(&gx, &gy, &gz, |cell| {
+                        let height = cell.iter().map(|p| p.2).sum::<f64>() / 4.0;
+                        let corners = [fit(cell[0]), fit(cell[1]), fit(cell[2]), fit(cell[3])];
+                        let normal = camera::surface_normal(corners[0], corners[1], corners[2], corners[3]);
+                        shapes.push(Shape::Face(colormap.color_in(height, heights.min, heights.max), corners, normal));
+                    });
+                }
+            }
+        }
+
+        Scene {
+            shapes,
+            ranges,
+            labels: [plot_builder.x_label, plot_builder.y_label, plot_builder.z_label],
+            title: plot_builder.title,
+            axes: plot_builder.axes,
+        }
+    }
+}
+
+/// Aligns text so that it sits on the side of a point facing away from the center of the plot
+fn align_away(dx: f64, dy: f64) -> (HAlign, VAlign) {
+    let length = dx.hypot(dy).max(1e-12);
+    let (dx, dy) = (dx / length, dy / length);
+    let halign = if dx > 0.3 { HAlign::Left } else if dx < -0.3 { HAlign::Right } else { HAlign::Center };
+    let valign = if dy > 0.3 { VAlign::Bottom } else if dy < -0.3 { VAlign::Top } else { VAlign::Center };
+    (halign, valign)
+}
+
+/// The edges of the cube, and for each axis the edge its ticks are labeled along.
+/// The labeled edges are the ones nearest the camera on the floor of the cube, plus a vertical edge at the side.
+fn cube_edges(camera: &Camera) -> (Vec<Edge>, [Edge; 3]) {
+    let mut edges = Vec::new();
+    for &a in &[-1.0, 1.0] {
+        for &b in &[-1.0, 1.0] {
+            edges.push([[-1.0, a, b], [1.0, a, b]]);
+            edges.push([[a, -1.0, b], [a, 1.0, b]]);
+            edges.push([[a, b, -1.0], [a, b, 1.0]]);
+        }
+    }
+
+    let direction = camera.direction();
+    let toward = |v: f64| if v >= 0.0 { 1.0 } else { -1.0 };
+    let (sx, sy) = (toward(direction[0]), toward(direction[1]));
+    let labeled = [
+        [[-1.0, sy, -1.0], [1.0, sy, -1.0]],
+        [[sx, -1.0, -1.0], [sx, 1.0, -1.0]],
+        [[sx, -sy, -1.0], [sx, -sy, 1.0]],
+    ];
+    (edges, labeled)
+}
+
+fn draw_scene(renderer: &mut dyn Drawable, scene: &Scene, camera: &Camera) {
+    let bgcol = f32_4_to_color([1.0, 1.0, 1.0, 1.0]);
+    let boxcol = [0.6, 0.6, 0.6, 1.0];
+    let textcol = f32_4_to_color([0.2, 0.2, 0.2, 1.0]);
+
+    renderer.set_color(bgcol);
+    renderer.clear();
+
+    // the plot fills the largest square that fits, with projected (0, 0) in the middle of the window
+    let (win_w, win_h) = renderer.get_size();
+    let unit = 2.0 * VIEW_SIZE / win_w.min(win_h).max(1.0);
+    let view = Range2d(
+        Range {
+            min: -win_w / 2.0 * unit,
+            max: win_w / 2.0 * unit,
+        },
+        Range {
+            min: -win_h / 2.0 * unit,
+            max: win_h / 2.0 * unit,
+        },
+    );
+    renderer.set_view(view);
+
+    let (edges, labeled) = cube_edges(camera);
+    let box_lines: Vec<Shape> = if scene.axes { edges.into_iter().map(|e| Shape::Line(boxcol, e, 1)).collect() } else { vec![] };
+
+    // the painter's algorithm: everything is drawn from the furthest to the nearest
+    let mut visible = Vec::new();
+    for shape in scene.shapes.iter().chain(&box_lines) {
+        let projected: Option<Vec<(f64, f64, f64)>> = camera.project(shape.points()).into_iter().collect();
+        if let Some(projected) = projected {
+            let depth = projected.iter().map(|p| p.2).sum::<f64>() / projected.len() as f64;
+            visible.push((depth, shape, projected.into_iter().map(|p| (p.0, p.1)).collect::<Vec<_>>()));
+        }
+    }
+    visible.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (_, shape, points) in &visible {
+        match **shape {
+            Shape::Line(color, _, thickness) => {
+                renderer.set_color(f32_4_to_color(color));
+                if thickness > 1 {
+                    renderer.thick_line(points[0], points[1], thickness);
+                } else {
+                    renderer.line(points[0], points[1]);
+                }
+            }
+            Shape::Point(color, _) => {
+                let (x, y) = points[0];
+                renderer.set_color(f32_4_to_color(color));
+                renderer.rectangle((x - 3.0 * unit, y - 3.0 * unit), (x + 3.0 * unit, y + 3.0 * unit));
+            }
+            Shape::Face(color, _, normal) => {
+                let light = camera::brightness(normal, camera) as f32;
+                let shaded = [color[0] * light, color[1] * light, color[2] * light, color[3]];
+                renderer.set_color(f32_4_to_color(shaded));
+                renderer.polygon(points);
+            }
+        }
+    }
+
+    renderer.set_color(textcol);
+    if scene.axes {
+        let gap = (TICK_LENGTH + PADDING / 2.0) * unit;
+        let text_h = renderer.text_size("0").1 * unit;
+        for axis in 0..3 {
+            let edge = labeled[axis];
+            let range = scene.ranges[axis];
+            let ticks: Vec<Tick> = Scale::Linear.ticks(range.min, range.max, 5);
+
+            for tick in &ticks {
+                let t = (tick.value - range.min) / range.size();
+                let mut point = edge[0];
+                point[axis] = -1.0 + 2.0 * t;
+                if let Some((x, y, _)) = camera.project(&[point])[0] {
+                    let (halign, valign) = align_away(x, y);
+                    let length = x.hypot(y).max(1e-12);
+                    renderer.text((x + x / length * gap, y + y / length * gap), &tick.label, halign, valign);
+                }
+            }
+
+            if let Some(ref label) = scene.labels[axis] {
+                let mut middle = edge[0];
+                middle[axis] = 0.0;
+                if let Some((x, y, _)) = camera.project(&[middle])[0] {
+                    let (halign, valign) = align_away(x, y);
+                    let length = x.hypot(y).max(1e-12);
+                    let offset = gap * 2.0 + text_h;
+                    renderer.text((x + x / length * offset, y + y / length * offset), label, halign, valign);
+                }
+            }
+        }
+    }
+
+    if let Some(ref title) = scene.title {
+        renderer.text((0.0, view.1.max - PADDING * unit), title, HAlign::Center, VAlign::Top);
+    }
+
+    renderer.present();
+}
+
+/// `draw_plot3d` shows a 3D plot until the window is closed
+pub fn draw_plot3d(renderer: &mut dyn Drawable, plot_builder: PlotBuilder3D, redraw: Redraw) {
+    let home = plot_builder.camera;
+    let mut camera = home;
+    let scene = Scene::new(plot_builder);
+
+    draw_scene(renderer, &scene, &camera);
+
+    // where the mouse was last, while the camera is being dragged
+    let mut drag: Option<(f64, f64)> = None;
+
+    // a `Redraw` wakes the plot up, unless the renderer can't be woken, in which case it looks every so often
+    let timeout = if renderer.waker().is_some() { None } else { Some(POLL_INTERVAL) };
+
+    'main: loop {
+        let mut update = false;
+        for event in renderer.wait_events(timeout) {
+            match event {
+                Event::Quit => break 'main,
+
+                Event::KeyDown(keycode) => {
+                    if keycode == 1 {
+                        //Keycode::Escape {
+                        break 'main;
+                    } else if keycode == RESET_KEY {
+                        camera = home;
+                        update = true;
+                    }
+                }
+                Event::MouseDown(MouseButton::Left, x, y) => {
+                    drag = Some((x, y));
+                }
+                Event::MouseUp(MouseButton::Left, _, _) => {
+                    drag = None;
+                }
+                Event::MouseMove(Some(MouseButton::Left), x, y) => {
+                    if let Some(last) = drag {
+                        // dragging right spins the plot to the right, dragging down tips it towards the viewer
+                        camera.orbit(-(x - last.0) * ORBIT_SPEED, (y - last.1) * ORBIT_SPEED);
+                        drag = Some((x, y));
+                        update = true;
+                    }
+                }
+                Event::MouseScroll(_x, y) => {
+                    camera.dolly(-y as f64);
+                    update = true;
+                }
+                Event::Resize(_, _) => {
+                    update = true;
+                }
+                _ => {}
+            }
+        }
+
+        update |= redraw.take();
+        if update {
+            draw_scene(renderer, &scene, &camera);
+        }
+    }
+}
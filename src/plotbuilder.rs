@@ -5,11 +5,64 @@
 
 use std::marker::Sync;
 
+use camera::Camera;
+use colormap::Colormap;
+use column::Column;
+use contour::Contour;
+use density::Density;
+use distribution::{BoxPlot, Violin};
+use finance::Candles;
+use pie::Pie;
+use polar::Polar;
+use quiver::Quiver;
+use datetime::Timestamp;
+use scale::{AxisKind, Scale};
+
+#[cfg(feature = "use-ndarray")]
+use column::Value;
+#[cfg(feature = "use-ndarray")]
+use ndarray::{ArrayBase, DataOwned, Ix2};
+
 pub type PlotFn = &'static (Fn(f64) -> f64 + Sync);
 pub type AnimFn = &'static (Fn(f64, f64) -> f64 + Sync);
 
+/// `Step` decides where a step plot changes from one value to the next
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Step {
+    /// Each value holds from the previous x value up to its own, so the step is at the start of each interval
+    Pre,
+
+    /// Each value holds from its own x value up to the next, so the step is at the end of each interval
+    Post,
+
+    /// The step is halfway between neighbouring x values
+    Mid,
+}
+
+impl Step {
+    /// `points` returns the corners of the step function through `xy`, to be joined with straight lines
+    pub fn points(&self, xy: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        let mut points = Vec::with_capacity(xy.len() * 3);
+        for (i, &(x, y)) in xy.iter().enumerate() {
+            if i > 0 {
+                let (px, py) = xy[i - 1];
+                match *self {
+                    Step::Pre => points.push((px, y)),
+                    Step::Post => points.push((x, py)),
+                    Step::Mid => {
+                        let mid = (px + x) / 2.0;
+                        points.push((mid, py));
+                        points.push((mid, y));
+                    }
+                }
+            }
+            points.push((x, y));
+        }
+        points
+    }
+}
+
 /// `PlotVals2D` provides all of the value data for an individual plot
-/// Note: Only `Xy()` and `XyColor()` do anything at the moment
 #[derive(Clone)]
 pub enum PlotVals2D {
     /// A simple x-y value line plot... now in a color of your choice!
@@ -20,10 +73,47 @@ pub enum PlotVals2D {
 
     /// A custom animation curve with a color
     AnimFunColor([f32; 4], AnimFn),
+
+    /// A contour plot of a scalar field on a grid
+    Contour(Contour),
+
+    /// An x-y line plot through columns of x and y values, which may be shared with the caller or kept in other number types
+    ColumnsColor([f32; 4], Column, Column),
+
+    /// A step function through the xy values
+    StepColor([f32; 4], Step, Vec<(f64, f64)>),
+
+    /// A vertical line from the baseline to each xy value, with a marker at the value
+    StemColor([f32; 4], f64, Vec<(f64, f64)>),
+
+    /// Vertical bars from zero up to each y value, centered on each x value
+    BarColor([f32; 4], Vec<(f64, f64)>),
+
+    /// Box-and-whisker plots of groups of samples, with the x position of each group
+    BoxPlot(Vec<f64>, BoxPlot),
+
+    /// Violin plots of groups of samples, with the x position of each group
+    Violin(Vec<f64>, Violin),
+
+    /// Arrows showing a vector field
+    Quiver(Quiver),
+
+    /// A 2D histogram or hexbin plot of scattered points
+    Density(Density),
+
+    /// Candlesticks, OHLC bars or volume bars of market data
+    Candles(Candles),
+
+    /// A pie or donut chart, which takes up the whole of its axes
+    Pie(Pie),
+
+    /// Any of the other plots, drawn against the secondary y-axis on the right instead of the main one
+    SecondaryY(Box<PlotVals2D>),
 }
 
-/// `PlotBuilder2D` contains all of the necessary information to create a series of stacked 2 dimensional plots.
-/// For the moment, only provide one `PlotVals2D`, otherwise things will probably go poorly.
+/// `PlotBuilder2D` contains all of the necessary information to create one set of 2 dimensional axes,
+/// with any number of plots stacked on top of each other.
+/// To show several sets of axes in one window, arrange them in a `figure::Figure`.
 #[derive(Clone)]
 pub struct PlotBuilder2D {
     /// **pvs** contains the **P**lot **V** alue **s**
@@ -41,27 +131,67 @@ pub struct PlotBuilder2D {
     /// **max_y** optionally defines the upper y bound. If `None`, it will be auto determined.
     pub max_y: Option<f64>,
 
-    /// A string to label the x-axis. (not implemented)
+    /// **x_scale** transforms the x values before they are drawn, e.g. `Scale::Log10` for a logarithmic x-axis.
+    /// Values the scale cannot show, like non-positive values on a log axis, leave gaps in the plot
+    /// and are ignored when the bounds are auto determined. Bounds the scale cannot show are ignored too.
+    pub x_scale: Scale,
+
+    /// **y_scale** transforms the y values before they are drawn, just like `x_scale`
+    pub y_scale: Scale,
+
+    /// **x_kind** decides what the x values mean, e.g. `AxisKind::DateTime` to label them as dates.
+    /// `add_time_xy` sets this for you.
+    pub x_kind: AxisKind,
+
+    /// **y_kind** decides what the y values mean, just like `x_kind`
+    pub y_kind: AxisKind,
+
+    /// **min_y2** optionally defines the lower bound of the secondary y-axis. If `None`, it will be auto determined.
+    pub min_y2: Option<f64>,
+
+    /// **max_y2** optionally defines the upper bound of the secondary y-axis. If `None`, it will be auto determined.
+    pub max_y2: Option<f64>,
+
+    /// **y2_scale** transforms the values on the secondary y-axis, just like `y_scale`
+    pub y2_scale: Scale,
+
+    /// **polar** optionally turns the axes into polar axes, where the x values are angles in radians and the y values are radii.
+    /// The scales, axis kinds and x and y bounds are ignored on polar axes; the radii shown are set in `Polar` instead.
+    /// Polar axes only draw lines, from `PlotVals2D::XyColor` and `PlotVals2D::ColumnsColor`, and bars, from `PlotVals2D::BarColor`.
+    /// Every other kind of plot, such as steps, stems, contours or densities, is left out.
+    pub polar: Option<Polar>,
+
+    /// A string to label the x-axis.
     pub x_label: Option<String>,
 
-    /// A string to label the y-axis. (not implemented)
+    /// A string to label the y-axis.
     pub y_label: Option<String>,
 
-    /// A string to label the chart. (not implemented)
+    /// A string to label the secondary y-axis.
+    pub y2_label: Option<String>,
+
+    /// A string to label the chart.
     pub title: Option<String>,
 
-    /// Whether or not to draw the y-axis. (not implemented)
+    /// Whether or not to draw the y-axis ticks and their labels.
     pub y_axis: bool,
 
-    /// Whether or not to draw the gridlines on the y-axis. (not implemented)
+    /// Whether or not to draw the gridlines on the y-axis.
     pub y_gridlines: bool,
 
-    /// Whether or not to draw the x-axis. (not implemented)
+    /// Whether or not to draw the secondary y-axis ticks and their labels.
+    /// The secondary y-axis only shows up when something is plotted against it.
+    pub y2_axis: bool,
+
+    /// Whether or not to draw the x-axis ticks and their labels.
     pub x_axis: bool,
 
-    /// Whether or not to draw the gridlines on the x-axis. (not implemented)
+    /// Whether or not to draw the gridlines on the x-axis.
     pub x_gridlines: bool,
 
+    /// Whether or not to follow the mouse with a crosshair, labeled with the coordinates it points at.
+    pub crosshair: bool,
+
     /// The font file to use for any text. (not implemented)
     pub font_path: String,
 }
@@ -78,13 +208,24 @@ impl PlotBuilder2D {
             max_x: None,
             min_y: None,
             max_y: None,
+            x_scale: Scale::Linear,
+            y_scale: Scale::Linear,
+            x_kind: AxisKind::Numeric,
+            y_kind: AxisKind::Numeric,
+            min_y2: None,
+            max_y2: None,
+            y2_scale: Scale::Linear,
+            polar: None,
             x_label: None,
             y_label: None,
+            y2_label: None,
             title: None,
             y_axis: true,
             y_gridlines: true,
+            y2_axis: true,
             x_axis: true,
             x_gridlines: true,
+            crosshair: true,
             font_path: DEFAULT_FONT.to_string(),
         }
     }
@@ -99,8 +240,301 @@ impl PlotBuilder2D {
         self.pvs.push(PlotVals2D::XyColor(color, xy));
     }
 
+    /// `add_color_xy_y2` is the same as `add_color_xy`, but plotted against the secondary y-axis on the right.
+    /// Use this for a series whose values have different units or magnitudes than the others.
+    pub fn add_color_xy_y2(&mut self, xy: Vec<(f64, f64)>, color: [f32; 4]) {
+        self.add_y2(PlotVals2D::XyColor(color, xy));
+    }
+
+    /// `add_xy` adds a `PlotVals2D::XyColor` from any pairs of numbers, such as an array of `(i32, f32)`s or an iterator
+    pub fn add_xy<I, X, Y>(&mut self, xy: I, color: [f32; 4])
+    where
+        I: IntoIterator<Item = (X, Y)>,
+        X: Into<f64>,
+        Y: Into<f64>,
+    {
+        let xy = xy.into_iter().map(|(x, y)| (x.into(), y.into())).collect();
+        self.pvs.push(PlotVals2D::XyColor(color, xy));
+    }
+
+    /// `add_xs_ys` is the same as `add_xy`, but with the x and y values given separately.
+    /// The line stops at the end of whichever runs out first.
+    pub fn add_xs_ys<X, Y>(&mut self, xs: X, ys: Y, color: [f32; 4])
+    where
+        X: IntoIterator,
+        Y: IntoIterator,
+        X::Item: Into<f64>,
+        Y::Item: Into<f64>,
+    {
+        self.add_xy(xs.into_iter().zip(ys), color);
+    }
+
+    /// `add_y` adds a `PlotVals2D::XyColor` from y values alone, at x = 0, 1, 2 and so on
+    pub fn add_y<Y>(&mut self, ys: Y, color: [f32; 4])
+    where
+        Y: IntoIterator,
+        Y::Item: Into<f64>,
+    {
+        let xy = ys.into_iter().enumerate().map(|(i, y)| (i as f64, y.into())).collect();
+        self.pvs.push(PlotVals2D::XyColor(color, xy));
+    }
+
+    /// `add_fn` adds a `PlotVals2D::XyColor` of the closure `f` at each of the x values `xs`,
+    /// e.g. `pb.add_fn(linspace(0, 10, 100), |x| x.sin(), color)`
+    pub fn add_fn<X, F, Y>(&mut self, xs: X, f: F, color: [f32; 4])
+    where
+        X: IntoIterator,
+        X::Item: Into<f64>,
+        F: Fn(f64) -> Y,
+        Y: Into<f64>,
+    {
+        let xy = xs.into_iter().map(|x| {
+            let x = x.into();
+            (x, f(x).into())
+        });
+        self.add_xy(xy, color);
+    }
+
+    /// `add_y2` adds any `PlotVals2D`, plotted against the secondary y-axis on the right
+    pub fn add_y2(&mut self, pv: PlotVals2D) {
+        self.pvs.push(PlotVals2D::SecondaryY(Box::new(pv)));
+    }
+
+    /// `add_columns` adds a `PlotVals2D::ColumnsColor` from separate x and y columns, such as `Vec<f32>`s or `Arc<[i16]>`s.
+    /// The values are plotted as they are, without being copied, so this is the way to plot very large datasets.
+    pub fn add_columns<X: Into<Column>, Y: Into<Column>>(&mut self, xs: X, ys: Y, color: [f32; 4]) {
+        self.pvs.push(PlotVals2D::ColumnsColor(color, xs.into(), ys.into()));
+    }
+
+    /// `add_time_xy` adds an `PlotVals2D::XyColor` whose x values are points in time, such as `SystemTime`s,
+    /// and switches the x-axis to date labels
+    pub fn add_time_xy<T: Timestamp>(&mut self, xy: Vec<(T, f64)>, color: [f32; 4]) {
+        let xy = xy.into_iter().map(|(t, y)| (t.epoch_seconds(), y)).collect();
+        self.x_kind = AxisKind::DateTime;
+        self.pvs.push(PlotVals2D::XyColor(color, xy));
+    }
+
+    /// `add_category_x` adds an `PlotVals2D::XyColor` whose x values are category names.
+    /// Each new name gets the next free position on a categorical x-axis, in the order the names are first seen.
+    pub fn add_category_x<S: AsRef<str>>(&mut self, xy: Vec<(S, f64)>, color: [f32; 4]) {
+        let xy = xy.into_iter().map(|(name, y)| (self.x_kind.category_position(name.as_ref()), y)).collect();
+        self.pvs.push(PlotVals2D::XyColor(color, xy));
+    }
+
+    /// `add_category_y` is the same as `add_category_x`, but with the categories on the y-axis
+    pub fn add_category_y<S: AsRef<str>>(&mut self, xy: Vec<(f64, S)>, color: [f32; 4]) {
+        let xy = xy.into_iter().map(|(x, name)| (x, self.y_kind.category_position(name.as_ref()))).collect();
+        self.pvs.push(PlotVals2D::XyColor(color, xy));
+    }
+
+    /// `add_step_xy` adds a `PlotVals2D::StepColor`, which holds each y value until the next step instead of
+    /// drawing a straight line between them, e.g. for sampled signals
+    pub fn add_step_xy(&mut self, xy: Vec<(f64, f64)>, step: Step, color: [f32; 4]) {
+        self.pvs.push(PlotVals2D::StepColor(color, step, xy));
+    }
+
+    /// `add_stem_xy` adds a `PlotVals2D::StemColor`, with a stem from `baseline` up or down to each xy value
+    pub fn add_stem_xy(&mut self, xy: Vec<(f64, f64)>, baseline: f64, color: [f32; 4]) {
+        self.pvs.push(PlotVals2D::StemColor(color, baseline, xy));
+    }
+
+    /// `add_color_bars` adds a `PlotVals2D::BarColor`, with a bar for each xy value
+    pub fn add_color_bars(&mut self, xy: Vec<(f64, f64)>, color: [f32; 4]) {
+        self.pvs.push(PlotVals2D::BarColor(color, xy));
+    }
+
+    /// `add_category_bars` adds a `PlotVals2D::BarColor` with a bar for each named category on the x-axis
+    pub fn add_category_bars<S: AsRef<str>>(&mut self, bars: Vec<(S, f64)>, color: [f32; 4]) {
+        let xy = bars.into_iter().map(|(name, y)| (self.x_kind.category_position(name.as_ref()), y)).collect();
+        self.pvs.push(PlotVals2D::BarColor(color, xy));
+    }
+
+    /// `add_contour` adds a `PlotVals2D::Contour`, drawn as contour lines or filled bands depending on `contour.filled`
+    pub fn add_contour(&mut self, contour: Contour) {
+        self.pvs.push(PlotVals2D::Contour(contour));
+    }
+
+    /// `add_box_plot` adds a `PlotVals2D::BoxPlot`, with a box for each group of samples.
+    /// The group names are categories on the x-axis, just like in `add_category_x`.
+    pub fn add_box_plot(&mut self, box_plot: BoxPlot) {
+        let positions = box_plot.groups.iter().map(|g| self.x_kind.category_position(&g.0)).collect();
+        self.pvs.push(PlotVals2D::BoxPlot(positions, box_plot));
+    }
+
+    /// `add_violin` adds a `PlotVals2D::Violin`, with a violin for each group of samples.
+    /// The group names are categories on the x-axis, just like in `add_category_x`.
+    pub fn add_violin(&mut self, violin: Violin) {
+        let positions = violin.groups.iter().map(|g| self.x_kind.category_position(&g.0)).collect();
+        self.pvs.push(PlotVals2D::Violin(positions, violin));
+    }
+
+    /// `add_quiver` adds a `PlotVals2D::Quiver`, with an arrow for each vector
+    pub fn add_quiver(&mut self, quiver: Quiver) {
+        self.pvs.push(PlotVals2D::Quiver(quiver));
+    }
+
+    /// `add_density` adds a `PlotVals2D::Density`, which bins the points again whenever the view changes
+    pub fn add_density(&mut self, density: Density) {
+        self.pvs.push(PlotVals2D::Density(density));
+    }
+
+    /// `add_candles` adds a `PlotVals2D::Candles`, and switches the x-axis to date labels
+    pub fn add_candles(&mut self, candles: Candles) {
+        self.x_kind = AxisKind::DateTime;
+        self.pvs.push(PlotVals2D::Candles(candles));
+    }
+
+    /// `add_pie` adds a `PlotVals2D::Pie`. The axes then only show pie charts, without any ticks or gridlines.
+    pub fn add_pie(&mut self, pie: Pie) {
+        self.pvs.push(PlotVals2D::Pie(pie));
+    }
+
     /// `add_fun_xy` adds a function (should not be used)
     pub fn add_fun_xy(&mut self, fun: PlotFn) {
         self.pvs.push(PlotVals2D::FunColor([1.0, 0.0, 0.0, 1.0], fun));
     }
 }
+
+#[cfg(feature = "use-ndarray")]
+impl PlotBuilder2D {
+    /// `add_array_columns` adds each column of `ys` as a line over `xs`, such as an `Array1`,
+    /// colored along `colormap` from the first column to the last.
+    /// `ys` may be an `Array2` or an `ArcArray2`, and every line reads from its buffer without copying it.
+    /// Plot a view with `view.to_owned()`, which copies it once.
+    pub fn add_array_columns<X, S, T>(&mut self, xs: X, ys: ArrayBase<S, Ix2>, colormap: Colormap)
+    where
+        X: Into<Column>,
+        S: DataOwned<Elem = T>,
+        T: Value,
+    {
+        let xs = xs.into();
+        let ys = ys.into_shared();
+        let last = ys.ncols().saturating_sub(1).max(1) as f64;
+        for j in 0..ys.ncols() {
+            self.add_columns(xs.clone(), Column::array_column(ys.clone(), j), colormap.color(j as f64 / last));
+        }
+    }
+}
+
+/// `PlotVals3D` provides all of the value data for an individual 3 dimensional plot.
+/// Surfaces take their values from a grid, where `zs[j][i]` is the height at `(xs[i], ys[j])`.
+#[derive(Clone)]
+pub enum PlotVals3D {
+    /// A line through xyz points, in a color of your choice
+    LineColor([f32; 4], Vec<(f64, f64, f64)>),
+
+    /// A mark at each xyz point
+    ScatterColor([f32; 4], Vec<(f64, f64, f64)>),
+
+    /// The grid lines of a surface, from `xs`, `ys` and `zs`
+    Wireframe([f32; 4], Vec<f64>, Vec<f64>, Vec<Vec<f64>>),
+
+    /// A shaded surface colored by height, from `xs`, `ys` and `zs`
+    Surface(Colormap, Vec<f64>, Vec<f64>, Vec<Vec<f64>>),
+}
+
+/// `PlotBuilder3D` contains all of the necessary information to create a 3 dimensional plot.
+/// Dragging the mouse over the plot window orbits the camera, and scrolling moves it closer or further away.
+#[derive(Clone)]
+pub struct PlotBuilder3D {
+    /// **pvs** contains the **P**lot **V** alue **s**
+    pub pvs: Vec<PlotVals3D>,
+
+    /// **camera** is where the plot is first viewed from
+    pub camera: Camera,
+
+    /// A string to label the x-axis.
+    pub x_label: Option<String>,
+
+    /// A string to label the y-axis.
+    pub y_label: Option<String>,
+
+    /// A string to label the z-axis.
+    pub z_label: Option<String>,
+
+    /// A string to label the chart.
+    pub title: Option<String>,
+
+    /// Whether or not to draw the box around the data, with ticks and their labels.
+    pub axes: bool,
+}
+
+impl PlotBuilder3D {
+    /// `new` creates an empty `PlotBuilder3D` with a box and ticks around the data, seen from the default `Camera`
+    pub fn new() -> PlotBuilder3D {
+        PlotBuilder3D {
+            pvs: vec![],
+            camera: Camera::new(),
+            x_label: None,
+            y_label: None,
+            z_label: None,
+            title: None,
+            axes: true,
+        }
+    }
+
+    /// `add_color_line` adds a `PlotVals3D::LineColor` through the xyz values
+    pub fn add_color_line(&mut self, xyz: Vec<(f64, f64, f64)>, color: [f32; 4]) {
+        self.pvs.push(PlotVals3D::LineColor(color, xyz));
+    }
+
+    /// `add_color_scatter` adds a `PlotVals3D::ScatterColor` with a mark at each xyz value
+    pub fn add_color_scatter(&mut self, xyz: Vec<(f64, f64, f64)>, color: [f32; 4]) {
+        self.pvs.push(PlotVals3D::ScatterColor(color, xyz));
+    }
+
+    /// `add_wireframe` adds a `PlotVals3D::Wireframe` of the surface `zs` over the grid `xs` by `ys`
+    pub fn add_wireframe(&mut self, xs: Vec<f64>, ys: Vec<f64>, zs: Vec<Vec<f64>>, color: [f32; 4]) {
+        self.pvs.push(PlotVals3D::Wireframe(color, xs, ys, zs));
+    }
+
+    /// `add_surface` adds a `PlotVals3D::Surface` of `zs` over the grid `xs` by `ys`, colored by `colormap`
+    pub fn add_surface(&mut self, xs: Vec<f64>, ys: Vec<f64>, zs: Vec<Vec<f64>>, colormap: Colormap) {
+        self.pvs.push(PlotVals3D::Surface(colormap, xs, ys, zs));
+    }
+}
+
+impl Default for PlotBuilder3D {
+    fn default() -> PlotBuilder3D {
+        PlotBuilder3D::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn iter_test() {
+        let mut pb = PlotBuilder2D::new();
+        let color = [0.0, 0.0, 0.0, 1.0];
+        pb.add_xy(vec![(1, 2.5f32), (2, 3.0)], color);
+        pb.add_xs_ys([0u8, 1, 2], vec![4i16, 5], color);
+        pb.add_y([7.0, 8.0], color);
+        pb.add_fn(0..3, |x| x * x, color);
+
+        let lines: Vec<Vec<(f64, f64)>> = pb
+            .pvs
+            .iter()
+            .filter_map(|pv| match pv {
+                PlotVals2D::XyColor(_, xy) => Some(xy.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines[0], vec![(1.0, 2.5), (2.0, 3.0)]);
+        assert_eq!(lines[1], vec![(0.0, 4.0), (1.0, 5.0)]);
+        assert_eq!(lines[2], vec![(0.0, 7.0), (1.0, 8.0)]);
+        assert_eq!(lines[3], vec![(0.0, 0.0), (1.0, 1.0), (2.0, 4.0)]);
+    }
+
+    #[test]
+    fn step_test() {
+        let xy = [(0.0, 1.0), (1.0, 2.0), (3.0, 0.0)];
+        assert_eq!(Step::Pre.points(&xy), vec![(0.0, 1.0), (0.0, 2.0), (1.0, 2.0), (1.0, 0.0), (3.0, 0.0)]);
+        assert_eq!(Step::Post.points(&xy), vec![(0.0, 1.0), (1.0, 1.0), (1.0, 2.0), (3.0, 2.0), (3.0, 0.0)]);
+        assert_eq!(
+            Step::Mid.points(&xy),
+            vec![(0.0, 1.0), (0.5, 1.0), (0.5, 2.0), (1.0, 2.0), (2.0, 2.0), (2.0, 0.0), (3.0, 0.0)]
+        );
+    }
+}
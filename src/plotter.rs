@@ -2,32 +2,217 @@
 //!
 //! Each plot runs asynchronously in a background thread. A `Plotter` creates and tracks these background threads.
 //!
-//! For now, `Plotter::plot2d` is the only supported plotting function. It takes a `PlotBuilder2D` containing all needed information.
+//! `Plotter::plot2d` takes a `PlotBuilder2D` containing all needed information for a single plot.
+//! `Plotter::plot_figure` takes a `Figure` to show a grid of plots in one window.
+//! `Plotter::plot3d` takes a `PlotBuilder3D`, shown with a camera that can be orbited by dragging and moved in and out by scrolling.
+//!
+//! Plots in separate windows can be linked with a `Link`, passed to `Plotter::plot2d_linked` or `Plotter::plot_figure_linked`.
+//! Zooming, panning or resetting one linked plot then moves all of them along the linked axes.
+//!
+//! `Plotter::redraw` hands out a `Redraw` for each plot, which has it drawn again from another thread.
 //!
 //! The `Plotter::join` function allows the thread that owns the `Plotter` to wait until the user has closed all open plot windows before continuing.
 
 use std::thread;
-use plotbuilder::PlotBuilder2D;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use plotbuilder::{PlotBuilder2D, PlotBuilder3D};
+use figure::Figure;
 use plot::Plot;
-use draw;
+use draw::{self, Range, Waker};
+
+/// `LinkAxes` selects which axes a `Link` keeps in step
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LinkAxes {
+    X,
+    Y,
+    Both,
+}
+
+/// The last view published on a `Link`, in data coordinates
+struct LinkState {
+    version: u64,
+    x: Option<Range>,
+    y: Option<Range>,
+
+    /// Wakes up each linked plot that is waiting for something to happen, by the id `add_waker` gave it
+    wakers: Vec<(u64, Waker)>,
+
+    /// The id of the next waker
+    next_waker: u64,
+}
+
+/// `Link` keeps the views of plots in separate windows in step.
+/// Clones of a `Link` are the same link, so every plot given one of them follows the others.
+///
+/// Views are exchanged as data values, so linked plots may use different scales.
+/// When a linked window holds a figure, its first subplot and the subplots sharing an axis with it follow the link.
+#[derive(Clone)]
+pub struct Link {
+    axes: LinkAxes,
+    state: Arc<Mutex<LinkState>>,
+}
+
+impl Link {
+    /// `new` creates a link that keeps the chosen axes in step
+    pub fn new(axes: LinkAxes) -> Link {
+        Link {
+            axes,
+            state: Arc::new(Mutex::new(LinkState {
+                version: 0,
+                x: None,
+                y: None,
+                wakers: Vec::new(),
+                next_waker: 0,
+            })),
+        }
+    }
+
+    /// Tells the other linked plots about a new view, returning the version the plot that changed has now seen
+    pub(crate) fn publish(&self, x: Range, y: Range) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.version += 1;
+        if self.axes != LinkAxes::Y {
+            state.x = Some(x);
+        }
+        if self.axes != LinkAxes::X {
+            state.y = Some(y);
+        }
+        for (_, waker) in &state.wakers {
+            waker.wake();
+        }
+        state.version
+    }
+
+    /// Wakes up `waker` whenever a linked plot publishes a new view, until `remove_waker` is given the id returned
+    pub(crate) fn add_waker(&self, waker: Waker) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_waker;
+        state.next_waker += 1;
+        state.wakers.push((id, waker));
+        id
+    }
+
+    /// Stops waking the waker added as `id`, once its plot has closed
+    pub(crate) fn remove_waker(&self, id: u64) {
+        self.state.lock().unwrap().wakers.retain(|&(waker, _)| waker != id);
+    }
+
+    /// Returns the linked ranges if they changed since version `seen`, and updates `seen`
+    pub(crate) fn changes(&self, seen: &mut u64) -> Option<(Option<Range>, Option<Range>)> {
+        let state = self.state.lock().unwrap();
+        if state.version == *seen {
+            return None;
+        }
+        *seen = state.version;
+        Some((state.x, state.y))
+    }
+}
+
+/// `Redraw` asks a running plot to draw itself again from another thread, such as on an animation timer,
+/// or once values it reads through a `Values` type of its own have changed.
+/// Clones of a `Redraw` redraw the same plot.
+#[derive(Clone)]
+pub struct Redraw {
+    pending: Arc<AtomicBool>,
+
+    /// Wakes the plot up to draw itself, if its `Drawable` can be woken
+    waker: Option<Waker>,
+}
+
+impl Redraw {
+    pub(crate) fn new(waker: Option<Waker>) -> Redraw {
+        Redraw {
+            pending: Arc::new(AtomicBool::new(false)),
+            waker,
+        }
+    }
+
+    /// `redraw` has the plot drawn again as soon as it can be. Asking again before then still only draws it once.
+    pub fn redraw(&self) {
+        self.pending.store(true, Ordering::SeqCst);
+        if let Some(ref waker) = self.waker {
+            waker.wake();
+        }
+    }
+
+    /// Whether the plot was asked to draw itself again since it last looked
+    pub(crate) fn take(&self) -> bool {
+        self.pending.swap(false, Ordering::SeqCst)
+    }
+}
 
 pub struct Plotter {
     plots: Vec<thread::JoinHandle<()>>,
+
+    /// A `Redraw` for each plot, in the order they were started
+    redraws: Vec<Redraw>,
 }
 
 impl Plotter {
     /// `new` creates a new `Plotter` object to manage asynchronous plots
     pub fn new() -> Plotter {
-        Plotter { plots: Vec::new() }
+        Plotter {
+            plots: Vec::new(),
+            redraws: Vec::new(),
+        }
     }
 
-    /// `plot2d` is currently the only supported plotting function. It takes a `PlotBuilder2D` containing all needed information.
+    /// `plot2d` takes a `PlotBuilder2D` containing all needed information.
     pub fn plot2d(&mut self, plotbuilder: PlotBuilder2D, drawable: Box<draw::Drawable>) {
+        let redraw = self.add_redraw(&*drawable);
+        self.plots.push(thread::spawn(
+            move || { Plot::new2d(plotbuilder, drawable, None, redraw); },
+        ));
+    }
+
+    /// `plot2d_linked` is like `plot2d`, but keeps the plot in step with every other plot given the same `link`.
+    pub fn plot2d_linked(&mut self, plotbuilder: PlotBuilder2D, drawable: Box<dyn draw::Drawable>, link: &Link) {
+        let link = link.clone();
+        let redraw = self.add_redraw(&*drawable);
+        self.plots.push(thread::spawn(
+            move || { Plot::new2d(plotbuilder, drawable, Some(link), redraw); },
+        ));
+    }
+
+    /// `plot_figure` shows every subplot of a `Figure` in one window.
+    pub fn plot_figure(&mut self, figure: Figure, drawable: Box<dyn draw::Drawable>) {
+        let redraw = self.add_redraw(&*drawable);
+        self.plots.push(thread::spawn(
+            move || { Plot::new_figure(figure, drawable, None, redraw); },
+        ));
+    }
+
+    /// `plot_figure_linked` is like `plot_figure`, but keeps every subplot in step with the other plots given the same `link`.
+    pub fn plot_figure_linked(&mut self, figure: Figure, drawable: Box<dyn draw::Drawable>, link: &Link) {
+        let link = link.clone();
+        let redraw = self.add_redraw(&*drawable);
         self.plots.push(thread::spawn(
-            move || { Plot::new2d(plotbuilder, drawable); },
+            move || { Plot::new_figure(figure, drawable, Some(link), redraw); },
         ));
     }
 
+    /// `plot3d` takes a `PlotBuilder3D` containing all needed information.
+    pub fn plot3d(&mut self, plotbuilder: PlotBuilder3D, drawable: Box<dyn draw::Drawable>) {
+        let redraw = self.add_redraw(&*drawable);
+        self.plots.push(thread::spawn(
+            move || { Plot::new3d(plotbuilder, drawable, redraw); },
+        ));
+    }
+
+    /// `redraw` returns a `Redraw` for the `plot`th plot this `Plotter` started, counting from 0,
+    /// or `None` if it hasn't started that many
+    pub fn redraw(&self, plot: usize) -> Option<Redraw> {
+        self.redraws.get(plot).cloned()
+    }
+
+    /// Makes the `Redraw` for a plot about to be started on `drawable`
+    fn add_redraw(&mut self, drawable: &dyn draw::Drawable) -> Redraw {
+        let redraw = Redraw::new(drawable.waker());
+        self.redraws.push(redraw.clone());
+        redraw
+    }
+
     /// The `disown` function allows the thread that owns the `Plotter` to keep going without either `join`ing manually or letting the `Drop` trait force a `join`.
     pub fn disown(self) {
         ::std::mem::forget(self);
@@ -54,7 +239,7 @@ impl Drop for Plotter {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "use-sdl2"))]
 mod test {
     use super::*;
     use plotbuilder::*;
@@ -0,0 +1,149 @@
+//! **polar** describes polar axes, where each point is an angle `theta` and a radius `r`.
+//!
+//! Polar plots are laid out on a unit disk: the center is the smallest radius shown, and the edge of the disk is the largest.
+//! Everything drawn is clipped to the disk.
+
+use std::f64;
+
+/// The number of straight pieces that make up a full circle
+pub const CIRCLE_SEGMENTS: usize = 128;
+
+/// `Polar` turns a set of axes into polar axes. The x values of the plots are the angles `theta`, in radians,
+/// and the y values are the radii `r`. Lines and bars are drawn, bars as wedges for rose diagrams; other plots are left out.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Polar {
+    /// **zero** is the direction that `theta = 0` points in, in degrees counterclockwise from the right.
+    /// `90.0` points it up, like a compass.
+    pub zero: f64,
+
+    /// **clockwise** makes `theta` increase clockwise instead of counterclockwise
+    pub clockwise: bool,
+
+    /// **min_r** is the radius at the center of the plot
+    pub min_r: f64,
+
+    /// **max_r** optionally defines the radius at the edge of the plot. If `None`, it will be auto determined.
+    pub max_r: Option<f64>,
+
+    /// **angle_step** is the number of degrees between the angular gridlines
+    pub angle_step: f64,
+}
+
+impl Polar {
+    /// `new` creates polar axes with `theta = 0` to the right, increasing counterclockwise, and gridlines every 45 degrees
+    pub fn new() -> Polar {
+        Polar {
+            zero: 0.0,
+            clockwise: false,
+            min_r: 0.0,
+            max_r: None,
+            angle_step: 45.0,
+        }
+    }
+
+    /// `direction` returns the angle on screen, in radians counterclockwise from the right, that `theta` points in
+    pub fn direction(&self, theta: f64) -> f64 {
+        let sense = if self.clockwise { -1.0 } else { 1.0 };
+        self.zero.to_radians() + sense * theta
+    }
+
+    /// `to_disk` places the point `(theta, r)` on the unit disk, where radius `min_r` is the center and `max_r` the edge.
+    /// Radii below `min_r` are drawn at the center.
+    pub fn to_disk(&self, theta: f64, r: f64, max_r: f64) -> (f64, f64) {
+        let radius = ((r - self.min_r) / (max_r - self.min_r)).max(0.0);
+        let angle = self.direction(theta);
+        (radius * angle.cos(), radius * angle.sin())
+    }
+}
+
+impl Default for Polar {
+    fn default() -> Polar {
+        Polar::new()
+    }
+}
+
+/// `circle` returns the points of a circle of `radius` around the center of the disk
+pub fn circle(radius: f64) -> Vec<(f64, f64)> {
+    (0..CIRCLE_SEGMENTS)
+        .map(|i| {
+            let angle = i as f64 / CIRCLE_SEGMENTS as f64 * 2.0 * f64::consts::PI;
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+/// `clip_to_disk` clips the line from `a` to `b` to the disk of the given radius around the center,
+/// returning `None` if none of it is inside
+pub fn clip_to_disk(a: (f64, f64), b: (f64, f64), radius: f64) -> Option<((f64, f64), (f64, f64))> {
+    let d = (b.0 - a.0, b.1 - a.1);
+
+    // solve |a + t d| = radius for t, and keep the part of 0..1 between the two solutions
+    let qa = d.0 * d.0 + d.1 * d.1;
+    let qb = 2.0 * (a.0 * d.0 + a.1 * d.1);
+    let qc = a.0 * a.0 + a.1 * a.1 - radius * radius;
+    if qa == 0.0 {
+        return if qc <= 0.0 { Some((a, b)) } else { None };
+    }
+
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let t0 = ((-qb - root) / (2.0 * qa)).max(0.0);
+    let t1 = ((-qb + root) / (2.0 * qa)).min(1.0);
+    if t0 >= t1 {
+        return None;
+    }
+
+    let at = |t: f64| (a.0 + d.0 * t, a.1 + d.1 * t);
+    Some((at(t0), at(t1)))
+}
+
+/// `wedge` returns the outline of a rose diagram wedge on the unit disk, from the center out to `radius`,
+/// between the screen angles `start` and `end` in radians
+pub fn wedge(start: f64, end: f64, radius: f64) -> Vec<(f64, f64)> {
+    let steps = ((end - start).abs() / (2.0 * f64::consts::PI) * CIRCLE_SEGMENTS as f64).ceil().max(1.0) as usize;
+    let mut points = vec![(0.0, 0.0)];
+    points.extend((0..steps + 1).map(|i| {
+        let angle = start + (end - start) * i as f64 / steps as f64;
+        (radius * angle.cos(), radius * angle.sin())
+    }));
+    points
+}
+
+/// `format_angle` writes an angle in degrees, e.g. `45°`
+pub fn format_angle(degrees: f64) -> String {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees == degrees.round() {
+        format!("{}\u{b0}", degrees as i64)
+    } else {
+        format!("{:.1}\u{b0}", degrees)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+        (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9
+    }
+
+    #[test]
+    fn polar_test() {
+        let mut polar = Polar::new();
+        assert!(close(polar.to_disk(0.0, 2.0, 4.0), (0.5, 0.0)));
+
+        polar.zero = 90.0;
+        polar.clockwise = true;
+        assert!(close(polar.to_disk(0.0, 4.0, 4.0), (0.0, 1.0)));
+        assert!(close(polar.to_disk(f64::consts::FRAC_PI_2, 4.0, 4.0), (1.0, 0.0)));
+
+        let (a, b) = clip_to_disk((-2.0, 0.0), (0.5, 0.0), 1.0).unwrap();
+        assert!(close(a, (-1.0, 0.0)) && close(b, (0.5, 0.0)));
+        assert!(clip_to_disk((-2.0, 2.0), (2.0, 2.0), 1.0).is_none());
+
+        assert_eq!(format_angle(-90.0), "270\u{b0}");
+    }
+}
@@ -0,0 +1,119 @@
+//! **quiver** describes vector field plots, with an arrow for the vector `(u, v)` at each point.
+//!
+//! Arrows are measured on screen rather than in data units: their length is a fraction of the size of the plot area,
+//! and their heads are a fixed number of pixels. Zooming moves the arrows apart or together, but keeps them legible.
+
+use std::f64;
+
+use colormap::Colormap;
+
+/// `Quiver` describes arrows at scattered points, or at the points of a grid with `Quiver::grid`
+#[derive(Clone, Debug)]
+pub struct Quiver {
+    /// **points** contains the position each arrow starts from
+    pub points: Vec<(f64, f64)>,
+
+    /// **vectors** contains the `(u, v)` components of each arrow, pointing right and up.
+    /// Arrows with non-finite components are left out.
+    pub vectors: Vec<(f64, f64)>,
+
+    /// **scale** optionally sets how long an arrow of magnitude 1 is, as a fraction of the smaller side of the plot area.
+    /// If `None`, it is picked so that the longest arrow is about as long as the spacing between arrows.
+    pub scale: Option<f64>,
+
+    /// **colormap** optionally colors each arrow by its magnitude instead of using `color`
+    pub colormap: Option<Colormap>,
+
+    /// **color** is the color of every arrow when there is no colormap
+    pub color: [f32; 4],
+
+    /// **key** optionally draws a reference arrow of this magnitude in the top right corner of the plot, labeled with its magnitude
+    pub key: Option<f64>,
+}
+
+impl Quiver {
+    /// `new` creates a dark blue arrow for each of the `vectors`, starting from the matching one of the `points`, scaled to fit
+    pub fn new(points: Vec<(f64, f64)>, vectors: Vec<(f64, f64)>) -> Quiver {
+        Quiver {
+            points,
+            vectors,
+            scale: None,
+            colormap: None,
+            color: [0.1, 0.1, 0.4, 1.0],
+            key: None,
+        }
+    }
+
+    /// `grid` creates an arrow for each point of a grid, where `(us[j][i], vs[j][i])` is the vector at `(xs[i], ys[j])`,
+    /// just like the values of a `Contour`
+    pub fn grid(xs: &[f64], ys: &[f64], us: &[Vec<f64>], vs: &[Vec<f64>]) -> Quiver {
+        let mut points = Vec::new();
+        let mut vectors = Vec::new();
+        for (j, &y) in ys.iter().enumerate() {
+            for (i, &x) in xs.iter().enumerate() {
+                let u = us.get(j).and_then(|row| row.get(i)).cloned().unwrap_or(f64::NAN);
+                let v = vs.get(j).and_then(|row| row.get(i)).cloned().unwrap_or(f64::NAN);
+                points.push((x, y));
+                vectors.push((u, v));
+            }
+        }
+        Quiver::new(points, vectors)
+    }
+
+    /// `magnitude_range` returns the smallest and largest lengths of the finite vectors
+    pub fn magnitude_range(&self) -> (f64, f64) {
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for &(u, v) in &self.vectors {
+            let m = u.hypot(v);
+            if m.is_finite() {
+                min = min.min(m);
+                max = max.max(m);
+            }
+        }
+        (min, max)
+    }
+
+    /// `auto_scale` returns `scale`, or if it is `None`, the scale that makes the longest arrow
+    /// about as long as the spacing between arrows spread evenly over the plot
+    pub fn auto_scale(&self) -> f64 {
+        if let Some(scale) = self.scale {
+            return scale;
+        }
+
+        let (_, max) = self.magnitude_range();
+        let count = self.vectors.iter().filter(|&&(u, v)| u.is_finite() && v.is_finite()).count();
+        if count == 0 || max <= 0.0 {
+            return 0.0;
+        }
+
+        // a little shorter than the spacing, so neighbouring arrows don't run into each other
+        0.9 / ((count as f64).sqrt() * max)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quiver_test() {
+        let xs = [0.0, 1.0, 2.0];
+        let ys = [0.0, 1.0];
+        let us = vec![vec![1.0, 0.0, 3.0], vec![0.0, f64::NAN, 0.0]];
+        let vs = vec![vec![0.0, 2.0, 4.0], vec![0.0, 0.0, 0.0]];
+
+        let quiver = Quiver::grid(&xs, &ys, &us, &vs);
+        assert_eq!(quiver.points.len(), 6);
+        assert_eq!(quiver.points[4], (1.0, 1.0));
+        assert_eq!(quiver.vectors[2], (3.0, 4.0));
+        assert_eq!(quiver.magnitude_range(), (0.0, 5.0));
+
+        // five finite arrows, the longest of magnitude 5
+        assert!((quiver.auto_scale() - 0.9 / (5f64.sqrt() * 5.0)).abs() < 1e-12);
+
+        let mut quiver = quiver;
+        quiver.scale = Some(0.1);
+        assert_eq!(quiver.auto_scale(), 0.1);
+    }
+}
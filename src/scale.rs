@@ -0,0 +1,347 @@
+//! **scale** provides the axis scales, which transform data values before they are mapped to the screen,
+//! plus the tick generation that goes with each of them.
+//!
+//! Plots are laid out and zoomed in *scaled* coordinates, so zooming a log axis zooms evenly across decades.
+
+use std::f64;
+
+/// `Scale` selects how values along an axis are mapped onto the screen
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Scale {
+    /// Evenly spaced values
+    Linear,
+
+    /// Base 10 logarithm. Non-positive values cannot be shown and are left out of the plot.
+    Log10,
+
+    /// Base 2 logarithm. Non-positive values cannot be shown and are left out of the plot.
+    Log2,
+
+    /// Natural logarithm. Non-positive values cannot be shown and are left out of the plot.
+    Ln,
+
+    /// Symmetric log: linear within the given threshold around zero, and logarithmic beyond it in both directions.
+    /// The threshold must be positive, and plots draw with a threshold of 1 in place of any other.
+    Symlog(f64),
+}
+
+/// `AxisKind` decides what the values along an axis mean, and so how its ticks are placed and labeled
+#[derive(Clone, Debug, PartialEq)]
+pub enum AxisKind {
+    /// Plain numbers
+    Numeric,
+
+    /// Points in time, as seconds since the Unix epoch. Ticks fall on round times and are labeled as UTC dates and times.
+    DateTime,
+
+    /// Named categories, placed one unit apart starting at 0 and labeled with their names.
+    /// Categorical axes are always linear, whatever their `Scale` says.
+    Categorical(Vec<String>),
+}
+
+impl AxisKind {
+    /// `category_position` finds where the category `name` sits on this axis, adding it after the existing
+    /// categories if it's new. An axis that wasn't categorical becomes categorical.
+    pub fn category_position(&mut self, name: &str) -> f64 {
+        if let AxisKind::Categorical(ref mut names) = *self {
+            return match names.iter().position(|n| n == name) {
+                Some(i) => i as f64,
+                None => {
+                    names.push(name.to_string());
+                    (names.len() - 1) as f64
+                }
+            };
+        }
+
+        *self = AxisKind::Categorical(vec![name.to_string()]);
+        0.0
+    }
+}
+
+/// `Tick` is a single tick mark on an axis
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tick {
+    /// The position of the tick, in data coordinates
+    pub value: f64,
+
+    /// The text written next to the tick. Empty for minor ticks.
+    pub label: String,
+
+    /// Major ticks are labeled and get a full gridline, minor ticks only get a short mark
+    pub major: bool,
+}
+
+impl Tick {
+    fn major(value: f64, label: String) -> Tick {
+        Tick { value, label, major: true }
+    }
+
+    fn minor(value: f64) -> Tick {
+        Tick {
+            value,
+            label: String::new(),
+            major: false,
+        }
+    }
+}
+
+impl Scale {
+    /// `checked` returns the scale with a symlog threshold that isn't a positive number replaced by 1
+    pub fn checked(self) -> Scale {
+        match self {
+            Scale::Symlog(threshold) if !(threshold > 0.0 && threshold.is_finite()) => Scale::Symlog(1.0),
+            scale => scale,
+        }
+    }
+
+    /// `forward` transforms a data value into scaled coordinates. Returns `NaN` for values the scale cannot show.
+    pub fn forward(&self, v: f64) -> f64 {
+        match *self {
+            Scale::Linear => v,
+            Scale::Log10 => if v > 0.0 { v.log10() } else { f64::NAN },
+            Scale::Log2 => if v > 0.0 { v.log2() } else { f64::NAN },
+            Scale::Ln => if v > 0.0 { v.ln() } else { f64::NAN },
+            Scale::Symlog(threshold) => {
+                if v.abs() <= threshold {
+                    v / threshold
+                } else {
+                    v.signum() * (1.0 + (v.abs() / threshold).log10())
+                }
+            }
+        }
+    }
+
+    /// `inverse` transforms a value in scaled coordinates back into a data value
+    pub fn inverse(&self, s: f64) -> f64 {
+        match *self {
+            Scale::Linear => s,
+            Scale::Log10 => 10f64.powf(s),
+            Scale::Log2 => 2f64.powf(s),
+            Scale::Ln => s.exp(),
+            Scale::Symlog(threshold) => {
+                if s.abs() <= 1.0 {
+                    s * threshold
+                } else {
+                    s.signum() * threshold * 10f64.powf(s.abs() - 1.0)
+                }
+            }
+        }
+    }
+
+    /// `ticks` generates roughly `count` labeled ticks covering the data range `min..max`
+    pub fn ticks(&self, min: f64, max: f64, count: usize) -> Vec<Tick> {
+        let count = count.max(2);
+        match *self {
+            Scale::Linear => linear_ticks(min, max, count),
+            Scale::Log10 => log_ticks(10.0, min, max, count),
+            Scale::Log2 => log_ticks(2.0, min, max, count),
+            Scale::Ln => log_ticks(f64::consts::E, min, max, count),
+            Scale::Symlog(threshold) => symlog_ticks(threshold, min, max, count),
+        }
+    }
+}
+
+/// `nice_step` rounds `x` up to 1, 2, 5 or 10 times a power of ten
+pub fn nice_step(x: f64) -> f64 {
+    let magnitude = 10f64.powf(x.log10().floor());
+    let fraction = x / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// `format_number` writes `v` with just enough precision to tell apart values `step` apart
+pub fn format_number(v: f64, step: f64) -> String {
+    let step = step.abs();
+    if v == 0.0 || v.abs() < step * 1e-9 {
+        return "0".to_string();
+    }
+
+    let magnitude = v.abs().log10().floor();
+    let step_magnitude = if step > 0.0 { step.log10().floor() } else { magnitude };
+    if !(-4.0..6.0).contains(&magnitude) {
+        let decimals = (magnitude - step_magnitude).clamp(0.0, 6.0) as usize;
+        format!("{:.*e}", decimals, v)
+    } else {
+        let decimals = (-step_magnitude).max(0.0) as usize;
+        format!("{:.*}", decimals, v)
+    }
+}
+
+/// Evenly spaced ticks at round values within `min..=max`
+fn linear_ticks(min: f64, max: f64, count: usize) -> Vec<Tick> {
+    if !min.is_finite() || !max.is_finite() || max <= min {
+        return vec![];
+    }
+
+    let step = nice_step((max - min) / count as f64);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..last + 1)
+        .map(|i| {
+            let value = i as f64 * step;
+            Tick::major(value, format_number(value, step))
+        })
+        .collect()
+}
+
+fn format_power(base: f64, exponent: i64) -> String {
+    if base == 10.0 {
+        let value = 10f64.powi(exponent as i32);
+        format_number(value, value)
+    } else if base == 2.0 && (0..=20).contains(&exponent) {
+        format!("{}", 1u64 << exponent)
+    } else if base == 2.0 {
+        format!("2^{}", exponent)
+    } else if exponent == 0 {
+        "1".to_string()
+    } else {
+        format!("e^{}", exponent)
+    }
+}
+
+/// Ticks at the powers of `base`, with minor ticks in between when they fit
+fn log_ticks(base: f64, min: f64, max: f64, count: usize) -> Vec<Tick> {
+    if min.is_nan() || min <= 0.0 || !max.is_finite() || max <= min {
+        return vec![];
+    }
+
+    let first = min.log(base).ceil() as i64;
+    let last = max.log(base).floor() as i64;
+
+    // less than two powers in view: plain round numbers read better
+    if last - first < 1 {
+        return linear_ticks(min, max, count);
+    }
+
+    let stride = ((last - first + 1) as usize).div_ceil(count) as i64;
+    let mut ticks = Vec::new();
+    for exponent in (first - 1)..(last + 1) {
+        let power = base.powi(exponent as i32);
+        if exponent >= first && (exponent - first) % stride == 0 {
+            ticks.push(Tick::major(power, format_power(base, exponent)));
+        }
+
+        // minor ticks at 2..9 times each decade, as long as every decade is labeled
+        if base == 10.0 && stride == 1 {
+            for m in 2..10 {
+                let value = power * m as f64;
+                if value >= min && value <= max {
+                    ticks.push(Tick::minor(value));
+                }
+            }
+        }
+    }
+    ticks
+}
+
+/// Ticks at zero and at powers of ten times the threshold on either side of it
+fn symlog_ticks(threshold: f64, min: f64, max: f64, count: usize) -> Vec<Tick> {
+    if !min.is_finite() || !max.is_finite() || max <= min {
+        return vec![];
+    }
+    // the thresholds `checked` replaces have no ticks of their own
+    if Scale::Symlog(threshold).checked() != Scale::Symlog(threshold) {
+        return vec![];
+    }
+
+    // a tiny threshold can put more decades between it and the largest value than an f64 holds
+    let largest = min.abs().max(max.abs());
+    let decades = if largest > threshold { (largest / threshold).log10().ceil().min(700.0) as i64 } else { 0 };
+
+    let mut values = vec![0.0];
+    for k in 0..(decades + 1) {
+        let v = threshold * 10f64.powi(k as i32);
+        values.push(v);
+        values.insert(0, -v);
+    }
+    let values: Vec<f64> = values.into_iter().filter(|&v| v >= min && v <= max).collect();
+
+    let stride = values.len().div_ceil(count).max(1);
+    values
+        .into_iter()
+        .filter(|v| *v == 0.0 || ((v.abs() / threshold).log10().round() as usize).is_multiple_of(stride))
+        .map(|v| Tick::major(v, format_number(v, v.abs())))
+        .collect()
+}
+
+/// `category_ticks` labels the categories within `min..=max`, leaving some out if there are more than `count`
+pub fn category_ticks(names: &[String], min: f64, max: f64, count: usize) -> Vec<Tick> {
+    let first = min.ceil().max(0.0) as usize;
+    let last = max.floor().min(names.len() as f64 - 1.0);
+    if last < first as f64 {
+        return vec![];
+    }
+    let last = last as usize;
+
+    let stride = (last - first + 1).div_ceil(count.max(1));
+    (first..last + 1)
+        .map(|i| {
+            let major = (i - first).is_multiple_of(stride);
+            Tick {
+                value: i as f64,
+                label: if major { names[i].clone() } else { String::new() },
+                major,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn forward_inverse_test() {
+        for scale in &[Scale::Linear, Scale::Log10, Scale::Log2, Scale::Ln, Scale::Symlog(2.0)] {
+            for &v in &[0.001, 0.5, 1.0, 3.0, 1234.5] {
+                assert!((scale.inverse(scale.forward(v)) - v).abs() < 1e-9 * v.max(1.0));
+            }
+        }
+        assert!(Scale::Log10.forward(0.0).is_nan());
+        assert!(Scale::Ln.forward(-1.0).is_nan());
+        assert_eq!(Scale::Symlog(2.0).forward(-1.0), -0.5);
+        assert_eq!(Scale::Symlog(2.0).forward(-200.0), -3.0);
+    }
+
+    #[test]
+    fn ticks_test() {
+        let labels = |ticks: Vec<Tick>| -> Vec<String> { ticks.into_iter().filter(|t| t.major).map(|t| t.label).collect() };
+
+        assert_eq!(labels(Scale::Linear.ticks(0.0, 1.0, 5)), vec!["0", "0.2", "0.4", "0.6", "0.8", "1.0"]);
+        assert_eq!(labels(Scale::Log10.ticks(0.05, 2000.0, 10)), vec!["0.1", "1", "10", "100", "1000"]);
+        assert_eq!(labels(Scale::Log2.ticks(1.0, 16.0, 10)), vec!["1", "2", "4", "8", "16"]);
+        assert_eq!(labels(Scale::Symlog(1.0).ticks(-100.0, 100.0, 10)), vec!["-100", "-10", "-1", "0", "1", "10", "100"]);
+
+        // thresholds that aren't positive have no ticks, and are replaced before plotting
+        assert!(Scale::Symlog(0.0).ticks(-100.0, 100.0, 10).is_empty());
+        assert!(Scale::Symlog(-1.0).ticks(-100.0, 100.0, 10).is_empty());
+        assert!(!Scale::Symlog(1e-320).ticks(-1e300, 1e300, 10).is_empty());
+        assert_eq!(Scale::Symlog(0.0).checked(), Scale::Symlog(1.0));
+        assert_eq!(Scale::Symlog(f64::NAN).checked(), Scale::Symlog(1.0));
+        assert_eq!(Scale::Symlog(2.0).checked(), Scale::Symlog(2.0));
+        assert_eq!(format_number(2.5e7, 1e6), "2.5e7");
+    }
+
+    #[test]
+    fn categories_test() {
+        let mut kind = AxisKind::Numeric;
+        assert_eq!(kind.category_position("b"), 0.0);
+        assert_eq!(kind.category_position("a"), 1.0);
+        assert_eq!(kind.category_position("b"), 0.0);
+        assert_eq!(kind, AxisKind::Categorical(vec!["b".to_string(), "a".to_string()]));
+
+        let names: Vec<String> = (0..10).map(|i| format!("c{}", i)).collect();
+        let ticks = category_ticks(&names, -0.5, 9.5, 5);
+        assert_eq!(ticks.len(), 10);
+        let labels: Vec<&str> = ticks.iter().filter(|t| t.major).map(|t| &t.label[..]).collect();
+        assert_eq!(labels, vec!["c0", "c2", "c4", "c6", "c8"]);
+    }
+}